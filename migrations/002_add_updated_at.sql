@@ -0,0 +1,5 @@
+-- Migration 002: Track when a link was last modified
+
+ALTER TABLE links ADD COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE links SET updated_at = created_at WHERE updated_at = '';
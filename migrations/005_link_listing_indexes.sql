@@ -0,0 +1,38 @@
+-- Migration 005: Filtering, sorting and keyset pagination for link listing
+
+-- Lowercased host of target_url, for domain filtering
+ALTER TABLE links ADD COLUMN target_host TEXT NOT NULL DEFAULT '';
+
+-- Backfill from target_url: strip the scheme, then path, userinfo and port
+UPDATE links
+SET target_host = parsed.host
+FROM (
+    SELECT id,
+           lower(CASE WHEN instr(hostport, ':') > 0
+                      THEN substr(hostport, 1, instr(hostport, ':') - 1)
+                      ELSE hostport END) AS host
+    FROM (
+        SELECT id,
+               CASE WHEN instr(authority, '@') > 0
+                    THEN substr(authority, instr(authority, '@') + 1)
+                    ELSE authority END AS hostport
+        FROM (
+            SELECT id,
+                   CASE WHEN instr(rest, '/') > 0
+                        THEN substr(rest, 1, instr(rest, '/') - 1)
+                        ELSE rest END AS authority
+            FROM (
+                SELECT id, substr(target_url, instr(target_url, '://') + 3) AS rest
+                FROM links
+            )
+        )
+    )
+) AS parsed
+WHERE links.id = parsed.id;
+
+-- Keyset pagination indexes, one per sort option (short_code is already unique)
+CREATE INDEX IF NOT EXISTS idx_links_created_at_id ON links(created_at, id);
+CREATE INDEX IF NOT EXISTS idx_links_expires_sort_id
+    ON links(COALESCE(expires_at, '9999-12-31T23:59:59+00:00'), id);
+
+CREATE INDEX IF NOT EXISTS idx_links_target_host ON links(target_host);
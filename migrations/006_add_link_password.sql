@@ -0,0 +1,3 @@
+-- Migration 006: Optional password protection for links
+
+ALTER TABLE links ADD COLUMN password_hash TEXT;
@@ -0,0 +1,5 @@
+-- Migration 007: Optional click limit for links (e.g. single-use links)
+
+-- NULL means unlimited; click_count only counts redirects of limited links
+ALTER TABLE links ADD COLUMN max_clicks INTEGER;
+ALTER TABLE links ADD COLUMN click_count INTEGER NOT NULL DEFAULT 0;
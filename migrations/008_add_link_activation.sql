@@ -0,0 +1,5 @@
+-- Migration 008: Scheduled activation for links
+
+-- Links redirect from active_from on; before that, to prelaunch_url or a holding page
+ALTER TABLE links ADD COLUMN active_from TEXT;
+ALTER TABLE links ADD COLUMN prelaunch_url TEXT;
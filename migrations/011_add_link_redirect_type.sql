@@ -0,0 +1,4 @@
+-- Migration 011: Per-link redirect status
+
+-- permanent, temporary, found or see_other; NULL for the server default
+ALTER TABLE links ADD COLUMN redirect_type TEXT;
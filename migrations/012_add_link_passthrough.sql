@@ -0,0 +1,4 @@
+-- Migration 012: Path and query passthrough
+
+-- target_wins, request_wins or keep_both; NULL when off
+ALTER TABLE links ADD COLUMN passthrough TEXT;
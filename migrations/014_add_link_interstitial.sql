@@ -0,0 +1,4 @@
+-- Migration 014: Interstitial preview page
+
+-- 1 to show the preview page with a continue button instead of redirecting
+ALTER TABLE links ADD COLUMN interstitial INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,44 @@
+-- Migration 001: Initial PostgreSQL schema
+-- Equivalent to SQLite migrations 001-005, with native types
+
+CREATE TABLE IF NOT EXISTS links (
+    id UUID PRIMARY KEY,
+    short_code TEXT NOT NULL UNIQUE,
+    target_url TEXT NOT NULL,
+    -- Lowercased host of target_url, for domain filtering
+    target_host TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_links_expires_at ON links(expires_at);
+CREATE INDEX IF NOT EXISTS idx_links_target_host ON links(target_host);
+
+-- Keyset pagination indexes, one per sort option
+CREATE INDEX IF NOT EXISTS idx_links_created_at_id ON links(created_at, id);
+CREATE INDEX IF NOT EXISTS idx_links_expires_sort_id
+    ON links(COALESCE(expires_at, '9999-12-31T23:59:59+00:00'::timestamptz), id);
+CREATE INDEX IF NOT EXISTS idx_links_short_code_c_id ON links((short_code COLLATE "C"), id);
+
+CREATE TABLE IF NOT EXISTS clicks (
+    id BIGSERIAL PRIMARY KEY,
+    link_id UUID NOT NULL REFERENCES links(id) ON DELETE CASCADE,
+    clicked_at TIMESTAMPTZ NOT NULL,
+    referrer TEXT,
+    user_agent TEXT,
+    ip_hash TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_clicks_link_id_clicked_at ON clicks(link_id, clicked_at);
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    prefix TEXT NOT NULL UNIQUE,
+    key_hash TEXT NOT NULL,
+    scopes TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
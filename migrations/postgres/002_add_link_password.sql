@@ -0,0 +1,3 @@
+-- Migration 002: Optional password protection for links
+
+ALTER TABLE links ADD COLUMN IF NOT EXISTS password_hash TEXT;
@@ -0,0 +1,5 @@
+-- Migration 003: Optional click limit for links (e.g. single-use links)
+
+-- NULL means unlimited; click_count only counts redirects of limited links
+ALTER TABLE links ADD COLUMN IF NOT EXISTS max_clicks BIGINT;
+ALTER TABLE links ADD COLUMN IF NOT EXISTS click_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Migration 004: Scheduled activation for links
+
+-- Links redirect from active_from on; before that, to prelaunch_url or a holding page
+ALTER TABLE links ADD COLUMN IF NOT EXISTS active_from TIMESTAMPTZ;
+ALTER TABLE links ADD COLUMN IF NOT EXISTS prelaunch_url TEXT;
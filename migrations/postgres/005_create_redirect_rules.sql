@@ -0,0 +1,10 @@
+-- Migration 005: Conditional redirect rules per link
+
+-- Rules are evaluated in position order; conditions are stored as JSON
+CREATE TABLE IF NOT EXISTS redirect_rules (
+    link_id UUID NOT NULL REFERENCES links(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    conditions JSONB NOT NULL,
+    target_url TEXT NOT NULL,
+    PRIMARY KEY (link_id, position)
+);
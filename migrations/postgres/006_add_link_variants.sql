@@ -0,0 +1,6 @@
+-- Migration 006: A/B split destinations
+
+-- JSON array of weighted destinations; NULL for links without a split
+ALTER TABLE links ADD COLUMN IF NOT EXISTS variants TEXT;
+-- Name of the variant a click was sent to, for split links
+ALTER TABLE clicks ADD COLUMN IF NOT EXISTS variant TEXT;
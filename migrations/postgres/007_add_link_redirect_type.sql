@@ -0,0 +1,4 @@
+-- Migration 007: Per-link redirect status
+
+-- permanent, temporary, found or see_other; NULL for the server default
+ALTER TABLE links ADD COLUMN IF NOT EXISTS redirect_type TEXT;
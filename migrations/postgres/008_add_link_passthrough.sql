@@ -0,0 +1,4 @@
+-- Migration 008: Path and query passthrough
+
+-- target_wins, request_wins or keep_both; NULL when off
+ALTER TABLE links ADD COLUMN IF NOT EXISTS passthrough TEXT;
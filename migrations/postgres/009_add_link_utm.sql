@@ -0,0 +1,10 @@
+-- Migration 009: UTM campaign parameters
+
+-- UTM parameters of target_url, for filtering links and stats by campaign
+ALTER TABLE links ADD COLUMN IF NOT EXISTS utm_source TEXT;
+ALTER TABLE links ADD COLUMN IF NOT EXISTS utm_medium TEXT;
+ALTER TABLE links ADD COLUMN IF NOT EXISTS utm_campaign TEXT;
+ALTER TABLE links ADD COLUMN IF NOT EXISTS utm_term TEXT;
+ALTER TABLE links ADD COLUMN IF NOT EXISTS utm_content TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_links_utm_campaign ON links(utm_campaign);
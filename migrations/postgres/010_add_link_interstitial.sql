@@ -0,0 +1,4 @@
+-- Migration 010: Interstitial preview page
+
+-- Show the preview page with a continue button instead of redirecting
+ALTER TABLE links ADD COLUMN IF NOT EXISTS interstitial BOOLEAN NOT NULL DEFAULT FALSE;
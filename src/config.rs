@@ -1,9 +1,13 @@
 use std::path::PathBuf;
 
+use chrono::Duration;
+
+use crate::domain::{RedirectType, parse_duration};
+
 /// Application configuration loaded from environment variables.
 #[derive(Debug, Clone)]
 pub struct Config {
-    /// Database URL (SQLite path)
+    /// Database URL (`sqlite:` path, or `postgres://` with the `postgres` feature)
     pub database_url: String,
     /// Base URL for generating short links (e.g., "https://s.domain.local")
     pub base_url: String,
@@ -17,10 +21,38 @@ pub struct Config {
     pub qr_branding_logo: Option<PathBuf>,
     /// QR code size in pixels
     pub qr_size: u32,
+    /// Decode rendered QR codes to check they scan, shrinking the logo if needed
+    pub qr_verify: bool,
+    /// Rendered QR codes kept in memory (0 to disable)
+    pub qr_cache_entries: usize,
+    /// Total size of the rendered QR codes kept in memory, in bytes
+    pub qr_cache_max_bytes: usize,
+    /// Optional directory keeping rendered QR codes across restarts
+    pub qr_cache_dir: Option<PathBuf>,
+    /// Total size of the rendered QR codes kept on disk, in bytes
+    pub qr_cache_disk_max_bytes: u64,
+    /// Threads rendering QR codes
+    pub qr_render_workers: usize,
+    /// QR renders allowed to wait for a free thread before requests are rejected
+    pub qr_render_queue: usize,
     /// Cleanup interval in minutes (0 to disable)
     pub cleanup_interval_minutes: u64,
     /// Optional admin secret
     pub admin_secret: Option<String>,
+    /// Require a scoped API key for link creation and QR generation
+    pub require_api_key: bool,
+    /// Salt used when hashing client IPs for click analytics
+    pub ip_hash_salt: String,
+    /// Failed password attempts allowed per minute per IP
+    pub unlock_attempts_per_ip: u32,
+    /// Failed password attempts allowed per minute per link
+    pub unlock_attempts_per_link: u32,
+    /// Shortest lifetime allowed for links (e.g. "1h")
+    pub min_ttl: Option<Duration>,
+    /// Longest lifetime allowed for links; links without a TTL get this one
+    pub max_ttl: Option<Duration>,
+    /// Redirect status for links that do not set their own
+    pub default_redirect_type: RedirectType,
 }
 
 impl Config {
@@ -62,6 +94,45 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::InvalidQrSize)?;
 
+        let qr_verify = std::env::var("QR_VERIFY")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidQrVerify)?;
+
+        let qr_cache_entries = std::env::var("QR_CACHE_ENTRIES")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidQrCacheLimit)?;
+
+        let qr_cache_max_bytes = std::env::var("QR_CACHE_MAX_BYTES")
+            .unwrap_or_else(|_| "67108864".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidQrCacheLimit)?;
+
+        let qr_cache_dir = std::env::var("QR_CACHE_DIR")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+
+        let qr_cache_disk_max_bytes = std::env::var("QR_CACHE_DISK_MAX_BYTES")
+            .unwrap_or_else(|_| "268435456".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidQrCacheLimit)?;
+
+        let qr_render_workers = match std::env::var("QR_RENDER_WORKERS") {
+            Ok(v) => v
+                .parse()
+                .ok()
+                .filter(|workers| *workers > 0)
+                .ok_or(ConfigError::InvalidQrRenderPool)?,
+            Err(_) => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+
+        let qr_render_queue = std::env::var("QR_RENDER_QUEUE")
+            .unwrap_or_else(|_| "32".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidQrRenderPool)?;
+
         let cleanup_interval_minutes = std::env::var("CLEANUP_INTERVAL_MINUTES")
             .unwrap_or_else(|_| "60".to_string())
             .parse()
@@ -69,6 +140,51 @@ impl Config {
 
         let admin_secret = std::env::var("ADMIN_SECRET").ok();
 
+        let require_api_key = std::env::var("REQUIRE_API_KEY")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidRequireApiKey)?;
+
+        // API keys can only be issued with the admin secret
+        if require_api_key && admin_secret.is_none() {
+            return Err(ConfigError::ApiKeysWithoutAdminSecret);
+        }
+
+        // Without a secret salt, hashes of the 2^32 IPv4 addresses can be reversed by
+        // brute force; the .env.example placeholder is as good as public
+        let ip_hash_salt = std::env::var("IP_HASH_SALT")
+            .ok()
+            .filter(|v| !v.is_empty() && v != "change_me")
+            .ok_or(ConfigError::MissingIpHashSalt)?;
+
+        let unlock_attempts_per_ip = std::env::var("UNLOCK_ATTEMPTS_PER_IP")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidUnlockAttempts)?;
+
+        let unlock_attempts_per_link = std::env::var("UNLOCK_ATTEMPTS_PER_LINK")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidUnlockAttempts)?;
+
+        let min_ttl = optional_duration("MIN_TTL")?;
+        let max_ttl = optional_duration("MAX_TTL")?;
+        if let (Some(min), Some(max)) = (min_ttl, max_ttl)
+            && min > max
+        {
+            return Err(ConfigError::InvalidTtlBounds(
+                "MIN_TTL is greater than MAX_TTL".to_string(),
+            ));
+        }
+
+        let default_redirect_type = std::env::var("REDIRECT_TYPE")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse())
+            .transpose()
+            .map_err(ConfigError::InvalidRedirectType)?
+            .unwrap_or_default();
+
         Ok(Config {
             database_url,
             base_url,
@@ -77,12 +193,38 @@ impl Config {
             rate_limit_per_minute,
             qr_branding_logo,
             qr_size,
+            qr_verify,
+            qr_cache_entries,
+            qr_cache_max_bytes,
+            qr_cache_dir,
+            qr_cache_disk_max_bytes,
+            qr_render_workers,
+            qr_render_queue,
             cleanup_interval_minutes,
             admin_secret,
+            require_api_key,
+            ip_hash_salt,
+            unlock_attempts_per_ip,
+            unlock_attempts_per_link,
+            min_ttl,
+            max_ttl,
+            default_redirect_type,
         })
     }
 }
 
+/// Read an optional duration (e.g. "90d") from an environment variable.
+fn optional_duration(name: &str) -> Result<Option<Duration>, ConfigError> {
+    std::env::var(name)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            parse_duration(&v)
+                .map_err(|e| ConfigError::InvalidTtlBounds(format!("{}: {}", name, e)))
+        })
+        .transpose()
+}
+
 #[derive(Debug, thiserror::Error)]
 #[allow(clippy::enum_variant_names)]
 pub enum ConfigError {
@@ -92,6 +234,24 @@ pub enum ConfigError {
     InvalidRateLimit,
     #[error("Invalid QR size value")]
     InvalidQrSize,
+    #[error("Invalid QR_VERIFY value (expected true or false)")]
+    InvalidQrVerify,
+    #[error("Invalid QR cache limit value")]
+    InvalidQrCacheLimit,
+    #[error("Invalid QR render pool value (QR_RENDER_WORKERS must be at least 1)")]
+    InvalidQrRenderPool,
     #[error("Invalid cleanup interval value")]
     InvalidCleanupInterval,
+    #[error("Invalid unlock attempts value")]
+    InvalidUnlockAttempts,
+    #[error("Invalid TTL bounds: {0}")]
+    InvalidTtlBounds(String),
+    #[error("Invalid REDIRECT_TYPE: {0}")]
+    InvalidRedirectType(String),
+    #[error("Invalid REQUIRE_API_KEY value (expected true or false)")]
+    InvalidRequireApiKey,
+    #[error("IP_HASH_SALT must be set to a random secret so hashed client IPs cannot be reversed")]
+    MissingIpHashSalt,
+    #[error("REQUIRE_API_KEY needs ADMIN_SECRET to be set so API keys can be issued")]
+    ApiKeysWithoutAdminSecret,
 }
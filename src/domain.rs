@@ -1,10 +1,39 @@
+#[path = "domain/api_key.rs"]
+mod api_key;
+#[path = "domain/click.rs"]
+mod click;
 #[path = "domain/link.rs"]
 mod link;
+#[path = "domain/link_query.rs"]
+mod link_query;
+#[path = "domain/passthrough.rs"]
+mod passthrough;
+#[path = "domain/redirect_rule.rs"]
+mod redirect_rule;
+#[path = "domain/redirect_type.rs"]
+mod redirect_type;
 #[path = "domain/short_code.rs"]
 mod short_code;
 #[path = "domain/ttl.rs"]
 mod ttl;
+#[path = "domain/utm.rs"]
+mod utm;
+#[path = "domain/variant.rs"]
+mod variant;
 
+pub use api_key::{ApiKey, ApiKeyResponse, Scope};
+pub use click::{CampaignStats, Click, DailyClicks, LinkStats, VariantClicks};
 pub use link::{Link, LinkResponse};
+pub use link_query::{
+    DEFAULT_PAGE_SIZE, LinkCursor, LinkPage, LinkQuery, LinkSort, LinkStatus, MAX_PAGE_SIZE,
+    NEVER_EXPIRES_SORT_KEY, SortOrder,
+};
+pub use passthrough::Passthrough;
+pub use redirect_rule::{
+    MAX_RULES_PER_LINK, Platform, RedirectRule, RuleConditions, TimeWindow, Visitor, select_rule,
+};
+pub use redirect_type::RedirectType;
 pub use short_code::ShortCode;
-pub use ttl::Ttl;
+pub use ttl::{Ttl, TtlBounds, format_duration, parse_duration};
+pub use utm::Utm;
+pub use variant::{MAX_VARIANTS, Variant, pick_variant, validate_variants};
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+/// Marker at the start of every API key, to make leaked keys easy to spot.
+const KEY_MARKER: &str = "qrl";
+
+/// Length of the public prefix used to look a key up.
+const PREFIX_LENGTH: usize = 8;
+
+/// Length of the secret part of a key.
+const SECRET_LENGTH: usize = 32;
+
+/// A permission that can be granted to an API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "links:create")]
+    LinksCreate,
+    #[serde(rename = "links:read")]
+    LinksRead,
+    #[serde(rename = "links:update")]
+    LinksUpdate,
+    #[serde(rename = "links:delete")]
+    LinksDelete,
+    #[serde(rename = "qr:generate")]
+    QrGenerate,
+}
+
+impl Scope {
+    /// Stable string form, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::LinksCreate => "links:create",
+            Scope::LinksRead => "links:read",
+            Scope::LinksUpdate => "links:update",
+            Scope::LinksDelete => "links:delete",
+            Scope::QrGenerate => "qr:generate",
+        }
+    }
+
+    /// Parse the string form of a scope.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "links:create" => Some(Scope::LinksCreate),
+            "links:read" => Some(Scope::LinksRead),
+            "links:update" => Some(Scope::LinksUpdate),
+            "links:delete" => Some(Scope::LinksDelete),
+            "qr:generate" => Some(Scope::QrGenerate),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A stored API key. Only the SHA-256 hash of the key is persisted.
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: Uuid,
+    /// Human-readable name (e.g., "marketing-site")
+    pub name: String,
+    /// Public part of the key, used for lookup
+    pub prefix: String,
+    /// Hex-encoded SHA-256 of the full key
+    pub key_hash: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Generate a new random key, returning the plaintext key and its public prefix.
+    /// Keys look like `qrl_<prefix>_<secret>`.
+    pub fn generate_secret() -> (String, String) {
+        let mut rng = rand::rng();
+        let mut random = |len: usize| -> String {
+            (0..len)
+                .map(|_| rng.sample(rand::distr::Alphanumeric) as char)
+                .collect()
+        };
+
+        let prefix = random(PREFIX_LENGTH);
+        let key = format!("{}_{}_{}", KEY_MARKER, prefix, random(SECRET_LENGTH));
+        (key, prefix)
+    }
+
+    /// Extract the lookup prefix from a plaintext key.
+    pub fn prefix_of(key: &str) -> Option<&str> {
+        let mut parts = key.splitn(3, '_');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(KEY_MARKER), Some(prefix), Some(_)) if prefix.len() == PREFIX_LENGTH => {
+                Some(prefix)
+            }
+            _ => None,
+        }
+    }
+
+    /// Hash a plaintext key for storage.
+    pub fn hash(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+
+    /// Check a plaintext key against the stored hash in constant time.
+    pub fn verify(&self, key: &str) -> bool {
+        let provided = Self::hash(key);
+        provided.as_bytes().ct_eq(self.key_hash.as_bytes()).into()
+    }
+
+    /// Whether the key can currently be used.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|exp| now < exp)
+    }
+
+    /// Whether the key grants the given scope.
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+/// Response DTO for an API key (never includes the key itself).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ApiKey> for ApiKeyResponse {
+    fn from(key: &ApiKey) -> Self {
+        ApiKeyResponse {
+            id: key.id,
+            name: key.name.clone(),
+            prefix: key.prefix.clone(),
+            scopes: key.scopes.clone(),
+            created_at: key.created_at,
+            expires_at: key.expires_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_key(key: &str) -> ApiKey {
+        ApiKey {
+            id: Uuid::new_v4(),
+            name: "test".to_string(),
+            prefix: ApiKey::prefix_of(key).unwrap().to_string(),
+            key_hash: ApiKey::hash(key),
+            scopes: vec![Scope::LinksRead],
+            created_at: Utc::now(),
+            expires_at: None,
+            revoked_at: None,
+        }
+    }
+
+    #[test]
+    fn test_generated_key_has_prefix() {
+        let (key, prefix) = ApiKey::generate_secret();
+        assert!(key.starts_with("qrl_"));
+        assert_eq!(ApiKey::prefix_of(&key), Some(prefix.as_str()));
+    }
+
+    #[test]
+    fn test_prefix_of_rejects_malformed_keys() {
+        assert_eq!(ApiKey::prefix_of("not-a-key"), None);
+        assert_eq!(ApiKey::prefix_of("qrl_short_secret"), None);
+        assert_eq!(ApiKey::prefix_of("abc_12345678_secret"), None);
+    }
+
+    #[test]
+    fn test_verify_key() {
+        let (key, _) = ApiKey::generate_secret();
+        let api_key = create_test_key(&key);
+        assert!(api_key.verify(&key));
+        assert!(!api_key.verify(&format!("{}x", key)));
+    }
+
+    #[test]
+    fn test_key_activity() {
+        let (key, _) = ApiKey::generate_secret();
+        let now = Utc::now();
+        let mut api_key = create_test_key(&key);
+        assert!(api_key.is_active(now));
+
+        api_key.expires_at = Some(now);
+        assert!(!api_key.is_active(now));
+
+        api_key.expires_at = None;
+        api_key.revoked_at = Some(now);
+        assert!(!api_key.is_active(now));
+    }
+
+    #[test]
+    fn test_scope_roundtrip() {
+        for scope in [
+            Scope::LinksCreate,
+            Scope::LinksRead,
+            Scope::LinksUpdate,
+            Scope::LinksDelete,
+            Scope::QrGenerate,
+        ] {
+            assert_eq!(Scope::parse(scope.as_str()), Some(scope));
+        }
+        assert_eq!(Scope::parse("links:write"), None);
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// Maximum stored length of free-form header values (referrer, user agent).
+const MAX_HEADER_LENGTH: usize = 512;
+
+/// A single recorded visit of a short link.
+#[derive(Debug, Clone)]
+pub struct Click {
+    /// The link that was followed
+    pub link_id: Uuid,
+    /// When the redirect was served
+    pub clicked_at: DateTime<Utc>,
+    /// Value of the Referer header, if any
+    pub referrer: Option<String>,
+    /// Value of the User-Agent header, if any
+    pub user_agent: Option<String>,
+    /// Salted SHA-256 of the client IP (raw IPs are never stored)
+    pub ip_hash: String,
+    /// Variant the visitor was sent to, for A/B split links
+    pub variant: Option<String>,
+}
+
+impl Click {
+    /// Build a click event, hashing the client IP with the given salt. No variant is set.
+    pub fn new(
+        link_id: Uuid,
+        clicked_at: DateTime<Utc>,
+        ip: IpAddr,
+        salt: &str,
+        referrer: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Self {
+        Click {
+            link_id,
+            clicked_at,
+            referrer: referrer.map(truncate),
+            user_agent: user_agent.map(truncate),
+            ip_hash: hash_ip(ip, salt),
+            variant: None,
+        }
+    }
+}
+
+/// Hash a client IP address with a salt so visitors can be counted without storing IPs.
+pub fn hash_ip(ip: IpAddr, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(ip.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn truncate(value: &str) -> String {
+    value.chars().take(MAX_HEADER_LENGTH).collect()
+}
+
+/// Aggregated click statistics for a link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkStats {
+    pub link_id: Uuid,
+    pub total_clicks: u64,
+    pub unique_visitors: u64,
+    /// Per-day time series (UTC), oldest first. Days without clicks are omitted.
+    pub daily: Vec<DailyClicks>,
+    /// Per-variant breakdown for A/B split links, by variant name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<VariantClicks>,
+}
+
+/// Aggregated click statistics across all links of a UTM campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignStats {
+    pub campaign: String,
+    /// Number of links tagged with the campaign
+    pub links: u64,
+    pub total_clicks: u64,
+    /// Distinct visitors across all the campaign's links
+    pub unique_visitors: u64,
+    /// Per-day time series (UTC), oldest first. Days without clicks are omitted.
+    pub daily: Vec<DailyClicks>,
+}
+
+/// Click counts for a single UTC day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyClicks {
+    pub date: NaiveDate,
+    pub clicks: u64,
+    pub unique_visitors: u64,
+}
+
+/// Click counts for a single variant of a split link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantClicks {
+    pub variant: String,
+    pub clicks: u64,
+    pub unique_visitors: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_ip_is_deterministic() {
+        let ip: IpAddr = "192.168.1.10".parse().unwrap();
+        assert_eq!(hash_ip(ip, "salt"), hash_ip(ip, "salt"));
+        assert_eq!(hash_ip(ip, "salt").len(), 64);
+    }
+
+    #[test]
+    fn test_hash_ip_depends_on_salt_and_ip() {
+        let ip1: IpAddr = "192.168.1.10".parse().unwrap();
+        let ip2: IpAddr = "192.168.1.11".parse().unwrap();
+        assert_ne!(hash_ip(ip1, "salt"), hash_ip(ip2, "salt"));
+        assert_ne!(hash_ip(ip1, "salt"), hash_ip(ip1, "other"));
+    }
+
+    #[test]
+    fn test_click_truncates_long_headers() {
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let long_agent = "x".repeat(MAX_HEADER_LENGTH * 2);
+        let click = Click::new(Uuid::new_v4(), Utc::now(), ip, "", None, Some(&long_agent));
+        assert_eq!(click.user_agent.unwrap().len(), MAX_HEADER_LENGTH);
+        assert!(click.referrer.is_none());
+    }
+}
@@ -1,9 +1,11 @@
+use argon2::Argon2;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
 
-use super::ShortCode;
+use super::{Passthrough, RedirectType, ShortCode, Utm, Variant};
 
 /// A shortened link.
 #[derive(Debug, Clone)]
@@ -16,11 +18,80 @@ pub struct Link {
     pub target_url: Url,
     /// When the link was created
     pub created_at: DateTime<Utc>,
+    /// When the link was last modified
+    pub updated_at: DateTime<Utc>,
     /// Optional expiration time
     pub expires_at: Option<DateTime<Utc>>,
+    /// Argon2 hash of the password required to follow the link
+    pub password_hash: Option<String>,
+    /// Optional number of redirects after which the link is used up
+    pub max_clicks: Option<u32>,
+    /// Redirects counted against `max_clicks`
+    pub click_count: u32,
+    /// Optional time from which the link redirects to its target
+    pub active_from: Option<DateTime<Utc>>,
+    /// Where to send visitors before `active_from` (a holding page if unset)
+    pub prelaunch_url: Option<Url>,
+    /// Weighted destinations splitting the traffic instead of `target_url` (none if empty)
+    pub variants: Vec<Variant>,
+    /// Redirect status for this link; the server default if unset
+    pub redirect_type: Option<RedirectType>,
+    /// Whether extra path segments and the query are forwarded to the destination
+    pub passthrough: Passthrough,
+    /// Whether visitors see a preview page with a continue button instead of a redirect
+    pub interstitial: bool,
 }
 
 impl Link {
+    /// A new link created at `now`, with a fresh ID, active right away, with no expiry,
+    /// password, click limit, variants, passthrough or interstitial.
+    pub fn new(short_code: ShortCode, target_url: Url, now: DateTime<Utc>) -> Self {
+        Link {
+            id: Uuid::new_v4(),
+            short_code,
+            target_url,
+            created_at: now,
+            updated_at: now,
+            expires_at: None,
+            password_hash: None,
+            max_clicks: None,
+            click_count: 0,
+            active_from: None,
+            prelaunch_url: None,
+            variants: Vec::new(),
+            redirect_type: None,
+            passthrough: Passthrough::Off,
+            interstitial: false,
+        }
+    }
+
+    /// Hash a link password for storage.
+    pub fn hash_password(password: &str) -> Result<String, String> {
+        let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>())
+            .map_err(|e| format!("Failed to encode salt: {}", e))?;
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| format!("Failed to hash password: {}", e))
+    }
+
+    /// Whether following the link requires a password.
+    pub fn is_protected(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    /// Check a password against the stored hash. Unprotected links accept any password.
+    pub fn verify_password(&self, password: &str) -> bool {
+        let Some(stored) = &self.password_hash else {
+            return true;
+        };
+        PasswordHash::new(stored).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+
     /// Check if this link has expired.
     pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
         match self.expires_at {
@@ -28,6 +99,16 @@ impl Link {
             None => false,
         }
     }
+
+    /// Check if this link is not active yet at `now`.
+    pub fn is_scheduled(&self, now: DateTime<Utc>) -> bool {
+        self.active_from.is_some_and(|from| now < from)
+    }
+
+    /// Check if this link has reached its click limit.
+    pub fn is_exhausted(&self) -> bool {
+        self.max_clicks.is_some_and(|max| self.click_count >= max)
+    }
 }
 
 /// Response DTO for a link.
@@ -38,7 +119,32 @@ pub struct LinkResponse {
     pub short_url: String,
     pub target_url: String,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
+    /// Whether following the link requires a password
+    pub password_protected: bool,
+    /// Whether visitors see a preview page before being redirected
+    pub interstitial: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_clicks: Option<u32>,
+    /// Redirects left before the link is used up, for links with a click limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_clicks: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_from: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prelaunch_url: Option<String>,
+    /// A/B split destinations, for split links
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<Variant>,
+    /// Redirect status set for this link, if it overrides the server default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_type: Option<RedirectType>,
+    #[serde(default, skip_serializing_if = "Passthrough::is_off")]
+    pub passthrough: Passthrough,
+    /// Campaign tracking parameters of the target URL
+    #[serde(default, skip_serializing_if = "Utm::is_empty")]
+    pub utm: Utm,
 }
 
 impl LinkResponse {
@@ -49,7 +155,20 @@ impl LinkResponse {
             short_url: format!("{}/{}", base_url.trim_end_matches('/'), link.short_code),
             target_url: link.target_url.to_string(),
             created_at: link.created_at,
+            updated_at: link.updated_at,
             expires_at: link.expires_at,
+            password_protected: link.is_protected(),
+            interstitial: link.interstitial,
+            max_clicks: link.max_clicks,
+            remaining_clicks: link
+                .max_clicks
+                .map(|max| max.saturating_sub(link.click_count)),
+            active_from: link.active_from,
+            prelaunch_url: link.prelaunch_url.as_ref().map(|url| url.to_string()),
+            variants: link.variants.clone(),
+            redirect_type: link.redirect_type,
+            passthrough: link.passthrough,
+            utm: Utm::from_url(&link.target_url),
         }
     }
 }
@@ -64,7 +183,17 @@ mod tests {
             short_code: ShortCode::from_existing("Ab3kP9x".to_string()),
             target_url: Url::parse("https://example.com").unwrap(),
             created_at: Utc::now(),
+            updated_at: Utc::now(),
             expires_at,
+            password_hash: None,
+            max_clicks: None,
+            click_count: 0,
+            active_from: None,
+            prelaunch_url: None,
+            variants: Vec::new(),
+            redirect_type: None,
+            passthrough: Passthrough::Off,
+            interstitial: false,
         }
     }
 
@@ -94,4 +223,42 @@ mod tests {
         let link = create_test_link(Some(past));
         assert!(link.is_expired(Utc::now()));
     }
+
+    #[test]
+    fn test_link_scheduled_until_active_from() {
+        let now = Utc::now();
+        let mut link = create_test_link(None);
+        assert!(!link.is_scheduled(now));
+
+        link.active_from = Some(now + chrono::Duration::hours(1));
+        assert!(link.is_scheduled(now));
+
+        link.active_from = Some(now);
+        assert!(!link.is_scheduled(now));
+    }
+
+    #[test]
+    fn test_link_exhausted_at_click_limit() {
+        let mut link = create_test_link(None);
+        assert!(!link.is_exhausted());
+
+        link.max_clicks = Some(1);
+        assert!(!link.is_exhausted());
+
+        link.click_count = 1;
+        assert!(link.is_exhausted());
+    }
+
+    #[test]
+    fn test_password_verification() {
+        let mut link = create_test_link(None);
+        assert!(!link.is_protected());
+        assert!(link.verify_password("anything"));
+
+        link.password_hash = Some(Link::hash_password("s3cret").unwrap());
+        assert!(link.is_protected());
+        assert!(link.verify_password("s3cret"));
+        assert!(!link.verify_password("wrong"));
+        assert!(!link.verify_password(""));
+    }
 }
@@ -0,0 +1,192 @@
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Link, LinkResponse};
+
+/// Default number of links per page.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of links per page.
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Sort key standing in for a missing expiry, so never-expiring links sort last
+/// in ascending order. Must match the expression index in the migrations.
+pub const NEVER_EXPIRES_SORT_KEY: &str = "9999-12-31T23:59:59+00:00";
+
+/// Lifecycle filter for link listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// Redirecting now: activated, not expired and not used up
+    Active,
+    /// Expiry date has passed or click limit reached
+    Expired,
+    /// No expiry date
+    NeverExpires,
+    /// Activation date is still in the future
+    Scheduled,
+}
+
+/// Field to sort links by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSort {
+    #[default]
+    CreatedAt,
+    ExpiresAt,
+    ShortCode,
+}
+
+impl LinkSort {
+    /// Stable name, used in cursors.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkSort::CreatedAt => "created_at",
+            LinkSort::ExpiresAt => "expires_at",
+            LinkSort::ShortCode => "short_code",
+        }
+    }
+
+    /// The value of this sort key for a link, as stored in the database.
+    pub fn key_of(&self, link: &Link) -> String {
+        match self {
+            LinkSort::CreatedAt => link.created_at.to_rfc3339(),
+            LinkSort::ExpiresAt => link
+                .expires_at
+                .map(|e| e.to_rfc3339())
+                .unwrap_or_else(|| NEVER_EXPIRES_SORT_KEY.to_string()),
+            LinkSort::ShortCode => link.short_code.as_str().to_string(),
+        }
+    }
+}
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Position after the last link of a page, for keyset pagination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkCursor {
+    pub sort: LinkSort,
+    pub key: String,
+    pub id: Uuid,
+}
+
+impl LinkCursor {
+    /// Cursor pointing just after the given link.
+    pub fn after(link: &Link, sort: LinkSort) -> Self {
+        LinkCursor {
+            sort,
+            key: sort.key_of(link),
+            id: link.id,
+        }
+    }
+
+    /// Encode as an opaque URL-safe string.
+    pub fn encode(&self) -> String {
+        let raw = format!("{}|{}|{}", self.sort.as_str(), self.id, self.key);
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    /// Decode a cursor produced by [`LinkCursor::encode`].
+    pub fn decode(value: &str) -> Result<Self, String> {
+        let invalid = || "Invalid cursor".to_string();
+
+        let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(value)
+            .map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+
+        let mut parts = raw.splitn(3, '|');
+        let sort = match parts.next() {
+            Some("created_at") => LinkSort::CreatedAt,
+            Some("expires_at") => LinkSort::ExpiresAt,
+            Some("short_code") => LinkSort::ShortCode,
+            _ => return Err(invalid()),
+        };
+        let id = parts
+            .next()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+        let key = parts.next().ok_or_else(invalid)?.to_string();
+
+        Ok(LinkCursor { sort, key, id })
+    }
+}
+
+/// Filters, sorting and page position for listing links.
+#[derive(Debug, Clone)]
+pub struct LinkQuery {
+    pub status: Option<LinkStatus>,
+    /// Only links created at or after this instant
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only links created strictly before this instant
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only links whose target host is this domain or one of its subdomains
+    pub domain: Option<String>,
+    /// Only links whose short code starts with this prefix (case-sensitive)
+    pub short_code_prefix: Option<String>,
+    /// Only links tagged with this UTM campaign (exact match)
+    pub campaign: Option<String>,
+    pub sort: LinkSort,
+    pub order: SortOrder,
+    pub limit: u32,
+    pub cursor: Option<LinkCursor>,
+}
+
+impl Default for LinkQuery {
+    fn default() -> Self {
+        LinkQuery {
+            status: None,
+            created_after: None,
+            created_before: None,
+            domain: None,
+            short_code_prefix: None,
+            campaign: None,
+            sort: LinkSort::default(),
+            order: SortOrder::default(),
+            limit: DEFAULT_PAGE_SIZE,
+            cursor: None,
+        }
+    }
+}
+
+/// A page of links.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkPage {
+    pub links: Vec<LinkResponse>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = LinkCursor {
+            sort: LinkSort::ExpiresAt,
+            key: "2025-01-01T00:00:00+00:00".to_string(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(LinkCursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_cursor_rejects_garbage() {
+        assert!(LinkCursor::decode("not a cursor").is_err());
+        assert!(LinkCursor::decode("").is_err());
+
+        let bad_sort = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(format!("size|{}|x", Uuid::new_v4()));
+        assert!(LinkCursor::decode(&bad_sort).is_err());
+    }
+}
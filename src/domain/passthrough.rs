@@ -0,0 +1,155 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use url::{Url, form_urlencoded};
+
+/// Whether a link forwards the rest of the request path and its query to the destination,
+/// and which value wins when a query key is in both the destination and the request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Passthrough {
+    /// Extra path segments are not found; the request query is ignored
+    #[default]
+    Off,
+    /// Forward the path and query; the destination's values win on conflicts
+    TargetWins,
+    /// Forward the path and query; the request's values replace the destination's
+    RequestWins,
+    /// Forward the path and query; conflicting keys keep both values
+    KeepBoth,
+}
+
+impl Passthrough {
+    pub fn is_off(&self) -> bool {
+        matches!(self, Passthrough::Off)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Passthrough::Off => "off",
+            Passthrough::TargetWins => "target_wins",
+            Passthrough::RequestWins => "request_wins",
+            Passthrough::KeepBoth => "keep_both",
+        }
+    }
+
+    /// Append a request's path suffix (still percent-encoded, without leading slash)
+    /// to a destination and merge the request query into the destination's.
+    pub fn apply(&self, destination: &Url, suffix: &str, query: Option<&str>) -> Url {
+        let mut url = destination.clone();
+        if self.is_off() {
+            return url;
+        }
+
+        if !suffix.is_empty() {
+            let base = url.path().trim_end_matches('/').to_string();
+            url.set_path(&format!("{}/{}", base, suffix));
+        }
+
+        let Some(query) = query.filter(|q| !q.is_empty()) else {
+            return url;
+        };
+        let incoming: Vec<(String, String)> = form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+        let existing: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        let in_request = |key: &str| incoming.iter().any(|(k, _)| k == key);
+        let in_target = |key: &str| existing.iter().any(|(k, _)| k == key);
+
+        let kept: Vec<_> = existing
+            .iter()
+            .filter(|(key, _)| *self != Passthrough::RequestWins || !in_request(key))
+            .collect();
+        let added: Vec<_> = incoming
+            .iter()
+            .filter(|(key, _)| *self != Passthrough::TargetWins || !in_target(key))
+            .collect();
+
+        if kept.is_empty() && added.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut()
+                .clear()
+                .extend_pairs(kept)
+                .extend_pairs(added);
+        }
+        url
+    }
+}
+
+impl FromStr for Passthrough {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Passthrough::Off),
+            "target_wins" => Ok(Passthrough::TargetWins),
+            "request_wins" => Ok(Passthrough::RequestWins),
+            "keep_both" => Ok(Passthrough::KeepBoth),
+            _ => Err(format!("Invalid passthrough mode '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Passthrough {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(mode: Passthrough, target: &str, suffix: &str, query: Option<&str>) -> String {
+        mode.apply(&Url::parse(target).unwrap(), suffix, query)
+            .to_string()
+    }
+
+    #[test]
+    fn test_passthrough_appends_path_suffix() {
+        let mode = Passthrough::TargetWins;
+        assert_eq!(
+            apply(mode, "https://docs.example.com", "guide/install", None),
+            "https://docs.example.com/guide/install"
+        );
+        assert_eq!(
+            apply(mode, "https://example.com/docs/", "a%20b", None),
+            "https://example.com/docs/a%20b"
+        );
+        assert_eq!(
+            apply(mode, "https://example.com/docs?v=2", "page", None),
+            "https://example.com/docs/page?v=2"
+        );
+    }
+
+    #[test]
+    fn test_passthrough_query_conflicts() {
+        let target = "https://example.com/?ref=qr&lang=en";
+        let query = Some("ref=mail&page=2");
+
+        assert_eq!(
+            apply(Passthrough::TargetWins, target, "", query),
+            "https://example.com/?ref=qr&lang=en&page=2"
+        );
+        assert_eq!(
+            apply(Passthrough::RequestWins, target, "", query),
+            "https://example.com/?lang=en&ref=mail&page=2"
+        );
+        assert_eq!(
+            apply(Passthrough::KeepBoth, target, "", query),
+            "https://example.com/?ref=qr&lang=en&ref=mail&page=2"
+        );
+    }
+
+    #[test]
+    fn test_passthrough_off_keeps_destination() {
+        assert_eq!(
+            apply(Passthrough::Off, "https://example.com/a", "b", Some("c=d")),
+            "https://example.com/a"
+        );
+        assert_eq!("keep_both".parse(), Ok(Passthrough::KeepBoth));
+        assert!("on".parse::<Passthrough>().is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Maximum number of redirect rules per link.
+pub const MAX_RULES_PER_LINK: usize = 20;
+
+/// Client platform, detected from the `User-Agent` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    Ios,
+    Android,
+    Windows,
+    Macos,
+    Linux,
+}
+
+impl Platform {
+    /// Detect the platform from a `User-Agent` value.
+    /// Mobile platforms are checked first, as their agents mention desktop ones
+    /// ("like Mac OS X", "Linux; Android").
+    pub fn detect(user_agent: &str) -> Option<Self> {
+        if ["iPhone", "iPad", "iPod"]
+            .iter()
+            .any(|device| user_agent.contains(device))
+        {
+            Some(Platform::Ios)
+        } else if user_agent.contains("Android") {
+            Some(Platform::Android)
+        } else if user_agent.contains("Windows") {
+            Some(Platform::Windows)
+        } else if user_agent.contains("Macintosh") || user_agent.contains("Mac OS X") {
+            Some(Platform::Macos)
+        } else if user_agent.contains("Linux") {
+            Some(Platform::Linux)
+        } else {
+            None
+        }
+    }
+}
+
+/// Daily time window at a fixed UTC offset.
+/// Wraps past midnight when `from` is after `to` (e.g. 22:00 to 06:00).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// Start of the window (inclusive)
+    pub from: NaiveTime,
+    /// End of the window (exclusive)
+    pub to: NaiveTime,
+    /// Offset the times are expressed in (e.g. `+02:00`), UTC by default
+    #[serde(default = "utc", with = "utc_offset")]
+    pub utc_offset: FixedOffset,
+}
+
+impl TimeWindow {
+    /// Check whether an instant falls within the window.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let time = now.with_timezone(&self.utc_offset).time();
+        if self.from <= self.to {
+            self.from <= time && time < self.to
+        } else {
+            time >= self.from || time < self.to
+        }
+    }
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).expect("zero offset is valid")
+}
+
+/// (De)serialize a UTC offset as `+HH:MM`.
+mod utc_offset {
+    use chrono::FixedOffset;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        offset: &FixedOffset,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(offset)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FixedOffset, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid UTC offset '{}'", s)))
+    }
+}
+
+/// Conditions of a redirect rule. All the conditions set must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleConditions {
+    /// Matches any of these platforms
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<Platform>,
+    /// Matches if the visitor's preferred language is one of these tags;
+    /// `fr` also matches `fr-CA`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// Matches during this daily time window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeWindow>,
+}
+
+/// A conditional destination for a link, evaluated before its target URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectRule {
+    #[serde(flatten)]
+    pub conditions: RuleConditions,
+    /// Where to redirect when the conditions match
+    pub url: Url,
+}
+
+impl RedirectRule {
+    /// Check that the rule has at least one well-formed condition.
+    pub fn validate(&self) -> Result<(), String> {
+        let conditions = &self.conditions;
+        if conditions.platforms.is_empty()
+            && conditions.languages.is_empty()
+            && conditions.time.is_none()
+        {
+            return Err("Each rule needs at least one condition".to_string());
+        }
+
+        for language in &conditions.languages {
+            let valid = !language.is_empty()
+                && language.len() <= 35
+                && language.split('-').all(|part| {
+                    !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric())
+                });
+            if !valid {
+                return Err(format!("Invalid language tag '{}'", language));
+            }
+        }
+
+        if let Some(time) = &conditions.time
+            && time.from == time.to
+        {
+            return Err("Time window must not be empty".to_string());
+        }
+
+        if !matches!(self.url.scheme(), "http" | "https") {
+            return Err(format!("Rule URL must be http or https: {}", self.url));
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the rule applies to a visitor.
+    pub fn matches(&self, visitor: &Visitor) -> bool {
+        let conditions = &self.conditions;
+
+        let platform_ok = conditions.platforms.is_empty()
+            || visitor
+                .platform
+                .is_some_and(|p| conditions.platforms.contains(&p));
+
+        let language_ok = conditions.languages.is_empty()
+            || visitor.language.as_deref().is_some_and(|preferred| {
+                conditions
+                    .languages
+                    .iter()
+                    .any(|tag| language_matches(tag, preferred))
+            });
+
+        let time_ok = conditions
+            .time
+            .is_none_or(|window| window.contains(visitor.now));
+
+        platform_ok && language_ok && time_ok
+    }
+}
+
+/// Check whether a rule's language tag covers a visitor's language (case-insensitive).
+fn language_matches(tag: &str, preferred: &str) -> bool {
+    preferred.eq_ignore_ascii_case(tag)
+        || (preferred.len() > tag.len()
+            && preferred.as_bytes()[tag.len()] == b'-'
+            && preferred[..tag.len()].eq_ignore_ascii_case(tag))
+}
+
+/// What redirect rules are matched against, taken from the request.
+#[derive(Debug, Clone)]
+pub struct Visitor {
+    pub platform: Option<Platform>,
+    /// Preferred language from `Accept-Language`
+    pub language: Option<String>,
+    pub now: DateTime<Utc>,
+}
+
+impl Visitor {
+    /// Build a visitor from the request headers.
+    pub fn from_headers(
+        user_agent: Option<&str>,
+        accept_language: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Visitor {
+            platform: user_agent.and_then(Platform::detect),
+            language: accept_language.and_then(preferred_language),
+            now,
+        }
+    }
+}
+
+/// The highest-weighted language of an `Accept-Language` value, ignoring `*`.
+fn preferred_language(accept_language: &str) -> Option<String> {
+    accept_language
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let weight = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && tag != "*" && weight > 0.0).then_some((tag, weight))
+        })
+        // max_by keeps the last of equal weights, so reverse to prefer the first listed
+        .rev()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(tag, _)| tag.to_string())
+}
+
+/// The destination of the first rule matching the visitor, if any.
+pub fn select_rule<'a>(rules: &'a [RedirectRule], visitor: &Visitor) -> Option<&'a Url> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(visitor))
+        .map(|rule| &rule.url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+    const ANDROID: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Mobile Safari/537.36";
+    const MAC: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_4) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15";
+
+    fn rule(json: serde_json::Value) -> RedirectRule {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn visitor(user_agent: &str, accept_language: &str) -> Visitor {
+        Visitor::from_headers(Some(user_agent), Some(accept_language), Utc::now())
+    }
+
+    #[test]
+    fn test_platform_detection() {
+        assert_eq!(Platform::detect(IPHONE), Some(Platform::Ios));
+        assert_eq!(Platform::detect(ANDROID), Some(Platform::Android));
+        assert_eq!(Platform::detect(MAC), Some(Platform::Macos));
+        assert_eq!(
+            Platform::detect("Mozilla/5.0 (Windows NT 10.0; Win64; x64)"),
+            Some(Platform::Windows)
+        );
+        assert_eq!(Platform::detect("curl/8.5.0"), None);
+    }
+
+    #[test]
+    fn test_preferred_language() {
+        assert_eq!(
+            preferred_language("en-US,en;q=0.9,fr;q=0.8").as_deref(),
+            Some("en-US")
+        );
+        assert_eq!(
+            preferred_language("de;q=0.5, fr-CA;q=0.9").as_deref(),
+            Some("fr-CA")
+        );
+        assert_eq!(preferred_language("*, es").as_deref(), Some("es"));
+        assert_eq!(preferred_language("fr;q=0"), None);
+        assert_eq!(preferred_language(""), None);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = vec![
+            rule(
+                serde_json::json!({ "platforms": ["ios"], "url": "https://apps.apple.com/app/id1" }),
+            ),
+            rule(
+                serde_json::json!({ "platforms": ["android"], "url": "https://play.google.com/store/apps/details?id=app" }),
+            ),
+            rule(serde_json::json!({ "languages": ["fr"], "url": "https://example.com/fr" })),
+        ];
+
+        let url = select_rule(&rules, &visitor(IPHONE, "fr-FR"));
+        assert_eq!(url.unwrap().as_str(), "https://apps.apple.com/app/id1");
+
+        let url = select_rule(&rules, &visitor(MAC, "fr-CA,en;q=0.5"));
+        assert_eq!(url.unwrap().as_str(), "https://example.com/fr");
+
+        assert!(select_rule(&rules, &visitor(MAC, "en-US,fr;q=0.5")).is_none());
+    }
+
+    #[test]
+    fn test_conditions_combine() {
+        let rule = rule(serde_json::json!({
+            "platforms": ["android"],
+            "languages": ["de"],
+            "url": "https://example.com/de/android"
+        }));
+
+        assert!(rule.matches(&visitor(ANDROID, "de-AT")));
+        assert!(!rule.matches(&visitor(ANDROID, "en")));
+        assert!(!rule.matches(&visitor(IPHONE, "de")));
+        assert!(!rule.matches(&Visitor::from_headers(None, None, Utc::now())));
+    }
+
+    #[test]
+    fn test_time_window() {
+        let night = TimeWindow {
+            from: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            utc_offset: FixedOffset::east_opt(2 * 3600).unwrap(),
+        };
+
+        // 21:30 UTC is 23:30 at +02:00
+        assert!(night.contains(Utc.with_ymd_and_hms(2025, 1, 1, 21, 30, 0).unwrap()));
+        assert!(night.contains(Utc.with_ymd_and_hms(2025, 1, 1, 3, 59, 0).unwrap()));
+        assert!(!night.contains(Utc.with_ymd_and_hms(2025, 1, 1, 4, 0, 0).unwrap()));
+        assert!(!night.contains(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()));
+
+        let parsed = rule(serde_json::json!({
+            "time": { "from": "22:00", "to": "06:00", "utc_offset": "+02:00" },
+            "url": "https://example.com/night"
+        }));
+        assert_eq!(parsed.conditions.time, Some(night));
+    }
+
+    #[test]
+    fn test_rule_validation() {
+        let no_condition = rule(serde_json::json!({ "url": "https://example.com" }));
+        assert!(no_condition.validate().is_err());
+
+        let bad_language =
+            rule(serde_json::json!({ "languages": ["fr_FR"], "url": "https://example.com" }));
+        assert!(bad_language.validate().is_err());
+
+        let bad_scheme =
+            rule(serde_json::json!({ "platforms": ["ios"], "url": "javascript:alert(1)" }));
+        assert!(bad_scheme.validate().is_err());
+
+        let app_store = rule(
+            serde_json::json!({ "platforms": ["ios"], "url": "https://apps.apple.com/app/id1" }),
+        );
+        assert!(app_store.validate().is_ok());
+    }
+}
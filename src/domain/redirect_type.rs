@@ -0,0 +1,91 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// HTTP status used when redirecting to a link's destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedirectType {
+    /// 308 Permanent Redirect, for permanent moves indexed by search engines
+    Permanent,
+    /// 307 Temporary Redirect
+    #[default]
+    Temporary,
+    /// 302 Found, for legacy clients
+    Found,
+    /// 303 See Other
+    SeeOther,
+}
+
+impl RedirectType {
+    /// The HTTP status code of this redirect.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            RedirectType::Permanent => 308,
+            RedirectType::Temporary => 307,
+            RedirectType::Found => 302,
+            RedirectType::SeeOther => 303,
+        }
+    }
+
+    /// Whether clients may remember the redirect instead of asking again.
+    pub fn is_permanent(&self) -> bool {
+        matches!(self, RedirectType::Permanent)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RedirectType::Permanent => "permanent",
+            RedirectType::Temporary => "temporary",
+            RedirectType::Found => "found",
+            RedirectType::SeeOther => "see_other",
+        }
+    }
+}
+
+impl FromStr for RedirectType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "permanent" => Ok(RedirectType::Permanent),
+            "temporary" => Ok(RedirectType::Temporary),
+            "found" => Ok(RedirectType::Found),
+            "see_other" => Ok(RedirectType::SeeOther),
+            _ => Err(format!(
+                "Invalid redirect type '{}' (expected permanent, temporary, found or see_other)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RedirectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_type_round_trip() {
+        for redirect_type in [
+            RedirectType::Permanent,
+            RedirectType::Temporary,
+            RedirectType::Found,
+            RedirectType::SeeOther,
+        ] {
+            assert_eq!(redirect_type.as_str().parse(), Ok(redirect_type));
+            assert_eq!(
+                serde_json::to_string(&redirect_type).unwrap(),
+                format!("\"{}\"", redirect_type)
+            );
+        }
+        assert!("moved".parse::<RedirectType>().is_err());
+        assert_eq!(RedirectType::default().status_code(), 307);
+    }
+}
@@ -6,6 +6,23 @@ const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnpqrstuvwxyz234567
 /// Length of generated short codes.
 const SHORT_CODE_LENGTH: usize = 7;
 
+/// Minimum length of a custom alias.
+const ALIAS_MIN_LENGTH: usize = 3;
+
+/// Maximum length of a custom alias.
+const ALIAS_MAX_LENGTH: usize = 64;
+
+/// Paths served by the application itself that cannot be used as aliases.
+const RESERVED_ALIASES: &[&str] = &[
+    "api",
+    "health",
+    "metrics",
+    "app.js",
+    "styles.css",
+    "index.html",
+    "favicon.ico",
+];
+
 /// A short code identifier for a link.
 /// Wraps a String to provide type safety and controlled generation.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -24,6 +41,41 @@ impl ShortCode {
         ShortCode(code)
     }
 
+    /// Create a ShortCode from a user-chosen alias (e.g., "spring-sale").
+    /// Aliases are 3-64 characters of ASCII letters, digits, '-', '_' or '.',
+    /// must start and end with a letter or digit, and cannot shadow a reserved path.
+    pub fn from_alias(alias: &str) -> Result<Self, String> {
+        let len = alias.chars().count();
+        if !(ALIAS_MIN_LENGTH..=ALIAS_MAX_LENGTH).contains(&len) {
+            return Err(format!(
+                "Alias must be between {} and {} characters",
+                ALIAS_MIN_LENGTH, ALIAS_MAX_LENGTH
+            ));
+        }
+
+        if let Some(c) = alias
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        {
+            return Err(format!("Alias contains invalid character '{}'", c));
+        }
+
+        let starts_ok = alias.starts_with(|c: char| c.is_ascii_alphanumeric());
+        let ends_ok = alias.ends_with(|c: char| c.is_ascii_alphanumeric());
+        if !starts_ok || !ends_ok {
+            return Err("Alias must start and end with a letter or digit".to_string());
+        }
+
+        if RESERVED_ALIASES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(alias))
+        {
+            return Err(format!("Alias '{}' is reserved", alias));
+        }
+
+        Ok(ShortCode(alias.to_string()))
+    }
+
     /// Create a ShortCode from an existing string (e.g., from database).
     pub fn from_existing(code: String) -> Self {
         ShortCode(code)
@@ -78,6 +130,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_alias_valid() {
+        let code = ShortCode::from_alias("spring-sale").unwrap();
+        assert_eq!(code.as_str(), "spring-sale");
+        assert!(ShortCode::from_alias("Q3_report.2025").is_ok());
+    }
+
+    #[test]
+    fn test_alias_length_bounds() {
+        assert!(ShortCode::from_alias("ab").is_err());
+        assert!(ShortCode::from_alias("abc").is_ok());
+        assert!(ShortCode::from_alias(&"a".repeat(ALIAS_MAX_LENGTH)).is_ok());
+        assert!(ShortCode::from_alias(&"a".repeat(ALIAS_MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_alias_invalid_characters() {
+        assert!(ShortCode::from_alias("spring sale").is_err());
+        assert!(ShortCode::from_alias("spring/sale").is_err());
+        assert!(ShortCode::from_alias("soldé").is_err());
+        assert!(ShortCode::from_alias("-spring").is_err());
+        assert!(ShortCode::from_alias("spring.").is_err());
+    }
+
+    #[test]
+    fn test_alias_reserved() {
+        assert!(ShortCode::from_alias("api").is_err());
+        assert!(ShortCode::from_alias("Health").is_err());
+        assert!(ShortCode::from_alias("app.js").is_err());
+    }
+
     #[test]
     fn test_short_codes_are_different() {
         let code1 = ShortCode::generate();
@@ -1,22 +1,30 @@
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
-/// Time-to-live presets for links.
-/// Only fixed presets are supported to keep the system predictable.
+/// Longest accepted custom duration (100 years).
+const MAX_DURATION_DAYS: i64 = 36_500;
+
+/// Time-to-live for links: a preset, a custom duration or an absolute expiry instant.
+/// Serialized as a string: `1_week`, `1_month`, `1_year`, `never`, a duration such as
+/// `36h` or `90d`, or an RFC 3339 datetime.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[serde(try_from = "String", into = "String")]
 pub enum Ttl {
     /// Link expires in 1 week
-    #[serde(rename = "1_week")]
     OneWeek,
     /// Link expires in 1 month (30 days)
-    #[serde(rename = "1_month")]
     OneMonth,
     /// Link expires in 1 year (365 days)
-    #[serde(rename = "1_year")]
     OneYear,
     /// Link never expires
     Never,
+    /// Link expires after a custom duration
+    After(Duration),
+    /// Link expires at a fixed instant
+    At(DateTime<Utc>),
 }
 
 impl Ttl {
@@ -28,10 +36,143 @@ impl Ttl {
             Ttl::OneMonth => Some(now + Duration::days(30)),
             Ttl::OneYear => Some(now + Duration::days(365)),
             Ttl::Never => None,
+            Ttl::After(duration) => Some(now + *duration),
+            Ttl::At(instant) => Some(*instant),
+        }
+    }
+}
+
+impl FromStr for Ttl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1_week" => Ok(Ttl::OneWeek),
+            "1_month" => Ok(Ttl::OneMonth),
+            "1_year" => Ok(Ttl::OneYear),
+            "never" => Ok(Ttl::Never),
+            _ if s.contains('T') => DateTime::parse_from_rfc3339(s)
+                .map(|dt| Ttl::At(dt.with_timezone(&Utc)))
+                .map_err(|_| format!("Invalid expiry datetime '{}' (expected RFC 3339)", s)),
+            _ => parse_duration(s).map(Ttl::After),
+        }
+    }
+}
+
+impl TryFrom<String> for Ttl {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Ttl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ttl::OneWeek => f.write_str("1_week"),
+            Ttl::OneMonth => f.write_str("1_month"),
+            Ttl::OneYear => f.write_str("1_year"),
+            Ttl::Never => f.write_str("never"),
+            Ttl::After(duration) => f.write_str(&format_duration(*duration)),
+            Ttl::At(instant) => f.write_str(&instant.to_rfc3339()),
         }
     }
 }
 
+impl From<Ttl> for String {
+    fn from(ttl: Ttl) -> Self {
+        ttl.to_string()
+    }
+}
+
+/// Parse a duration made of a positive integer and a unit:
+/// `s` (seconds), `m` (minutes), `h` (hours), `d` (days) or `w` (weeks), e.g. `36h`.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid duration '{}' (expected e.g. 30m, 36h or 90d)", s);
+
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (amount, unit) = s.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return Err(invalid()),
+    };
+
+    if amount == 0 {
+        return Err(format!("Duration '{}' must be positive", s));
+    }
+    amount
+        .checked_mul(seconds_per_unit)
+        .filter(|&secs| secs <= MAX_DURATION_DAYS * 86_400)
+        .map(Duration::seconds)
+        .ok_or_else(|| format!("Duration '{}' exceeds {} days", s, MAX_DURATION_DAYS))
+}
+
+/// Format a duration in its largest whole unit, the inverse of [`parse_duration`].
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds();
+    match secs {
+        _ if secs != 0 && secs % (7 * 86_400) == 0 => format!("{}w", secs / (7 * 86_400)),
+        _ if secs != 0 && secs % 86_400 == 0 => format!("{}d", secs / 86_400),
+        _ if secs != 0 && secs % 3600 == 0 => format!("{}h", secs / 3600),
+        _ if secs != 0 && secs % 60 == 0 => format!("{}m", secs / 60),
+        _ => format!("{}s", secs),
+    }
+}
+
+/// Server-configured limits on how long links may live.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TtlBounds {
+    /// Shortest allowed lifetime
+    pub min: Option<Duration>,
+    /// Longest allowed lifetime; links without a TTL get this one
+    pub max: Option<Duration>,
+}
+
+impl TtlBounds {
+    /// Expiry of a link given its TTL at `now`, checked against the bounds.
+    /// Without a TTL, the link expires after the maximum lifetime, if any.
+    pub fn expiry(
+        &self,
+        ttl: Option<Ttl>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, String> {
+        let Some(ttl) = ttl else {
+            return Ok(self.max.map(|max| now + max));
+        };
+
+        let Some(expires_at) = ttl.expires_at(now) else {
+            return match self.max {
+                Some(max) => Err(format!("Links must expire within {}", format_duration(max))),
+                None => Ok(None),
+            };
+        };
+
+        let lifetime = expires_at - now;
+        if lifetime <= Duration::zero() {
+            return Err("Expiry must be in the future".to_string());
+        }
+        if let Some(min) = self.min
+            && lifetime < min
+        {
+            return Err(format!("TTL must be at least {}", format_duration(min)));
+        }
+        if let Some(max) = self.max
+            && lifetime > max
+        {
+            return Err(format!("TTL must be at most {}", format_duration(max)));
+        }
+
+        Ok(Some(expires_at))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +197,82 @@ mod tests {
         let expires = Ttl::OneYear.expires_at(now).unwrap();
         assert_eq!((expires - now).num_days(), 365);
     }
+
+    #[test]
+    fn test_ttl_parse_presets_and_custom_values() {
+        let ttl: Ttl = serde_json::from_str("\"1_month\"").unwrap();
+        assert_eq!(ttl, Ttl::OneMonth);
+        assert_eq!("never".parse::<Ttl>(), Ok(Ttl::Never));
+
+        assert_eq!("36h".parse::<Ttl>(), Ok(Ttl::After(Duration::hours(36))));
+        assert_eq!("90d".parse::<Ttl>(), Ok(Ttl::After(Duration::days(90))));
+
+        let at: Ttl = "2030-06-01T18:00:00+02:00".parse().unwrap();
+        assert_eq!(
+            at.expires_at(Utc::now()).unwrap().to_rfc3339(),
+            "2030-06-01T16:00:00+00:00"
+        );
+
+        for invalid in [
+            "",
+            "36",
+            "h",
+            "0d",
+            "-1d",
+            "1.5h",
+            "3 days",
+            "1y",
+            "2030-13-01T00:00:00Z",
+        ] {
+            assert!(
+                invalid.parse::<Ttl>().is_err(),
+                "{} should be rejected",
+                invalid
+            );
+        }
+        assert!("36501d".parse::<Ttl>().is_err());
+    }
+
+    #[test]
+    fn test_ttl_serializes_back_to_string() {
+        assert_eq!(serde_json::to_string(&Ttl::OneWeek).unwrap(), "\"1_week\"");
+        assert_eq!(
+            serde_json::to_string(&Ttl::After(Duration::hours(36))).unwrap(),
+            "\"36h\""
+        );
+        assert_eq!(format_duration(Duration::days(14)), "2w");
+        assert_eq!(format_duration(Duration::seconds(90)), "90s");
+    }
+
+    #[test]
+    fn test_ttl_bounds() {
+        let now = Utc::now();
+        let bounds = TtlBounds {
+            min: Some(Duration::hours(1)),
+            max: Some(Duration::days(90)),
+        };
+
+        assert_eq!(
+            bounds.expiry(Some(Ttl::OneMonth), now),
+            Ok(Some(now + Duration::days(30)))
+        );
+        assert_eq!(bounds.expiry(None, now), Ok(Some(now + Duration::days(90))));
+        assert!(
+            bounds
+                .expiry(Some(Ttl::After(Duration::minutes(30))), now)
+                .is_err()
+        );
+        assert!(bounds.expiry(Some(Ttl::OneYear), now).is_err());
+        assert!(bounds.expiry(Some(Ttl::Never), now).is_err());
+        assert!(
+            bounds
+                .expiry(Some(Ttl::At(now - Duration::hours(1))), now)
+                .is_err()
+        );
+
+        let unbounded = TtlBounds::default();
+        assert_eq!(unbounded.expiry(None, now), Ok(None));
+        assert_eq!(unbounded.expiry(Some(Ttl::Never), now), Ok(None));
+        assert!(unbounded.expiry(Some(Ttl::At(now)), now).is_err());
+    }
 }
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Maximum length of a single UTM value.
+const MAX_VALUE_LENGTH: usize = 200;
+
+/// Campaign tracking (UTM) parameters of a link's target URL.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Utm {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medium: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub term: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+impl Utm {
+    pub fn is_empty(&self) -> bool {
+        self.fields().iter().all(|(_, value)| value.is_none())
+    }
+
+    /// Query parameter names and values, in the conventional order.
+    fn fields(&self) -> [(&'static str, Option<&String>); 5] {
+        [
+            ("utm_source", self.source.as_ref()),
+            ("utm_medium", self.medium.as_ref()),
+            ("utm_campaign", self.campaign.as_ref()),
+            ("utm_term", self.term.as_ref()),
+            ("utm_content", self.content.as_ref()),
+        ]
+    }
+
+    /// Check that every set value is non-blank and at most 200 characters long.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in self.fields() {
+            if let Some(value) = value
+                && (value.trim().is_empty() || value.chars().count() > MAX_VALUE_LENGTH)
+            {
+                return Err(format!(
+                    "{} must be 1 to {} characters",
+                    name, MAX_VALUE_LENGTH
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the UTM parameters of a URL; the first value wins for repeated keys.
+    pub fn from_url(url: &Url) -> Self {
+        let get = |name: &str| {
+            url.query_pairs()
+                .find(|(key, value)| key == name && !value.is_empty())
+                .map(|(_, value)| value.into_owned())
+        };
+        Utm {
+            source: get("utm_source"),
+            medium: get("utm_medium"),
+            campaign: get("utm_campaign"),
+            term: get("utm_term"),
+            content: get("utm_content"),
+        }
+    }
+
+    /// These parameters, with unset ones taken from `fallback`.
+    pub fn or(self, fallback: Utm) -> Utm {
+        Utm {
+            source: self.source.or(fallback.source),
+            medium: self.medium.or(fallback.medium),
+            campaign: self.campaign.or(fallback.campaign),
+            term: self.term.or(fallback.term),
+            content: self.content.or(fallback.content),
+        }
+    }
+
+    /// Set these parameters on a URL, replacing any values it already has for
+    /// them and keeping its other query parameters.
+    pub fn apply(&self, url: &Url) -> Url {
+        let mut url = url.clone();
+        if self.is_empty() {
+            return url;
+        }
+
+        let set: Vec<(&str, &String)> = self
+            .fields()
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .collect();
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| !set.iter().any(|(name, _)| key == name))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(kept)
+            .extend_pairs(set);
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spring_sale() -> Utm {
+        Utm {
+            source: Some("newsletter".to_string()),
+            medium: Some("email".to_string()),
+            campaign: Some("spring sale".to_string()),
+            ..Utm::default()
+        }
+    }
+
+    #[test]
+    fn test_utm_apply_merges_into_query() {
+        let url = Url::parse("https://shop.example.com/?lang=en&utm_source=old").unwrap();
+        let tagged = spring_sale().apply(&url);
+
+        assert_eq!(
+            tagged.as_str(),
+            "https://shop.example.com/?lang=en&utm_source=newsletter&utm_medium=email&utm_campaign=spring+sale"
+        );
+        assert_eq!(Utm::from_url(&tagged), spring_sale());
+
+        let plain = Url::parse("https://example.com/a").unwrap();
+        assert_eq!(Utm::default().apply(&plain), plain);
+    }
+
+    #[test]
+    fn test_utm_validation() {
+        assert!(spring_sale().validate().is_ok());
+        assert!(Utm::default().validate().is_ok());
+
+        let blank = Utm {
+            campaign: Some("  ".to_string()),
+            ..Utm::default()
+        };
+        assert!(blank.validate().is_err());
+
+        let long = Utm {
+            term: Some("x".repeat(201)),
+            ..Utm::default()
+        };
+        assert!(long.validate().is_err());
+    }
+}
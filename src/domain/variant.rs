@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Maximum number of variants of a split link.
+pub const MAX_VARIANTS: usize = 10;
+
+/// Maximum weight of a single variant.
+const MAX_WEIGHT: u32 = 10_000;
+
+/// Maximum length of a variant name.
+const MAX_NAME_LENGTH: usize = 32;
+
+/// One weighted destination of an A/B split link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Variant {
+    /// Label used in the sticky cookie and the statistics
+    pub name: String,
+    pub url: Url,
+    /// Share of the traffic, relative to the other variants' weights
+    pub weight: u32,
+}
+
+/// Check a list of variants: none (no split), or 2 to [`MAX_VARIANTS`] uniquely named,
+/// positively weighted destinations.
+pub fn validate_variants(variants: &[Variant]) -> Result<(), String> {
+    if variants.len() == 1 || variants.len() > MAX_VARIANTS {
+        return Err(format!(
+            "A split link needs between 2 and {} variants",
+            MAX_VARIANTS
+        ));
+    }
+
+    for (index, variant) in variants.iter().enumerate() {
+        let name = &variant.name;
+        if name.is_empty()
+            || name.len() > MAX_NAME_LENGTH
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Variant name '{}' must be 1 to {} letters, digits, '-' or '_'",
+                name, MAX_NAME_LENGTH
+            ));
+        }
+        if variants[..index].iter().any(|other| other.name == *name) {
+            return Err(format!("Duplicate variant name '{}'", name));
+        }
+        if variant.weight == 0 || variant.weight > MAX_WEIGHT {
+            return Err(format!(
+                "Variant '{}' weight must be between 1 and {}",
+                name, MAX_WEIGHT
+            ));
+        }
+        if !matches!(variant.url.scheme(), "http" | "https") {
+            return Err(format!(
+                "Variant '{}' URL must be http or https: {}",
+                name, variant.url
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick the variant for a visitor: the one named by their sticky assignment if it
+/// still exists, otherwise a weighted draw using `roll` (any value, reduced modulo
+/// the total weight). Returns None for links without variants.
+pub fn pick_variant<'a>(
+    variants: &'a [Variant],
+    sticky: Option<&str>,
+    roll: u64,
+) -> Option<&'a Variant> {
+    if let Some(variant) = sticky.and_then(|name| variants.iter().find(|v| v.name == name)) {
+        return Some(variant);
+    }
+
+    let total: u64 = variants.iter().map(|v| u64::from(v.weight)).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut point = roll % total;
+    variants.iter().find(|variant| {
+        let weight = u64::from(variant.weight);
+        if point < weight {
+            true
+        } else {
+            point -= weight;
+            false
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(name: &str, weight: u32) -> Variant {
+        Variant {
+            name: name.to_string(),
+            url: Url::parse(&format!("https://example.com/{}", name)).unwrap(),
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_pick_variant_by_weight() {
+        let variants = vec![variant("a", 1), variant("b", 3)];
+
+        assert_eq!(pick_variant(&variants, None, 0).unwrap().name, "a");
+        for roll in 1..4 {
+            assert_eq!(pick_variant(&variants, None, roll).unwrap().name, "b");
+        }
+        assert_eq!(pick_variant(&variants, None, 4).unwrap().name, "a");
+        assert!(pick_variant(&[], None, 0).is_none());
+    }
+
+    #[test]
+    fn test_pick_variant_is_sticky() {
+        let variants = vec![variant("a", 1), variant("b", 1)];
+
+        assert_eq!(pick_variant(&variants, Some("b"), 0).unwrap().name, "b");
+        // A removed variant gets a fresh draw
+        assert_eq!(pick_variant(&variants, Some("c"), 0).unwrap().name, "a");
+    }
+
+    #[test]
+    fn test_validate_variants() {
+        assert!(validate_variants(&[]).is_ok());
+        assert!(validate_variants(&[variant("a", 50), variant("b", 50)]).is_ok());
+
+        assert!(validate_variants(&[variant("a", 1)]).is_err());
+        assert!(validate_variants(&[variant("a", 1), variant("a", 1)]).is_err());
+        assert!(validate_variants(&[variant("a", 0), variant("b", 1)]).is_err());
+        assert!(validate_variants(&[variant("a b", 1), variant("b", 1)]).is_err());
+
+        let mut ftp = variant("b", 1);
+        ftp.url = Url::parse("ftp://example.com").unwrap();
+        assert!(validate_variants(&[variant("a", 1), ftp]).is_err());
+    }
+}
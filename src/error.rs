@@ -3,7 +3,11 @@ use axum::{
     http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
 use serde::Serialize;
+use url::Url;
+
+use crate::domain::Scope;
 
 /// Application-wide error type.
 #[derive(Debug, thiserror::Error)]
@@ -14,6 +18,15 @@ pub enum AppError {
     #[error("Link has expired")]
     LinkExpired,
 
+    #[error("Link has reached its click limit")]
+    LinkExhausted,
+
+    #[error("Link is not active yet")]
+    LinkNotActive {
+        active_from: DateTime<Utc>,
+        prelaunch_url: Option<Url>,
+    },
+
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
@@ -23,19 +36,52 @@ pub enum AppError {
     #[error("Admin rights required")]
     AdminRightsRequired,
 
+    #[error("Invalid or expired API key")]
+    InvalidApiKey,
+
+    #[error("API key lacks required scope: {0}")]
+    InsufficientScope(Scope),
+
+    #[error("API key not found")]
+    ApiKeyNotFound,
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Invalid password")]
+    InvalidPassword,
+
+    #[error("Invalid alias: {0}")]
+    InvalidAlias(String),
+
+    #[error("Invalid TTL: {0}")]
+    InvalidTtl(String),
+
+    #[error("Short code already in use: {0}")]
+    ShortCodeTaken(String),
+
     #[error("Failed to generate short code after multiple attempts")]
     ShortCodeExhausted,
 
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
+    #[error("Invalid QR style: {0}")]
+    InvalidQrStyle(String),
+
     #[error("QR generation failed: {0}")]
     QrGeneration(String),
 
+    #[error("QR rendering is at capacity")]
+    QrRenderBusy,
+
     #[error("Internal server error")]
     Internal(String),
 }
 
+/// Seconds a client should wait before retrying a QR request rejected at capacity.
+const QR_RENDER_RETRY_AFTER: u64 = 1;
+
 /// Error response body sent to clients.
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
@@ -49,6 +95,15 @@ impl IntoResponse for AppError {
         let (status, error, message) = match &self {
             AppError::LinkNotFound => (StatusCode::NOT_FOUND, "not_found", None),
             AppError::LinkExpired => (StatusCode::GONE, "link_expired", None),
+            AppError::LinkExhausted => (StatusCode::GONE, "link_exhausted", None),
+            AppError::LinkNotActive { active_from, .. } => (
+                StatusCode::FORBIDDEN,
+                "link_not_active",
+                Some(format!(
+                    "Link becomes active at {}",
+                    active_from.to_rfc3339()
+                )),
+            ),
             AppError::InvalidUrl(msg) => {
                 (StatusCode::BAD_REQUEST, "invalid_url", Some(msg.clone()))
             }
@@ -64,7 +119,31 @@ impl IntoResponse for AppError {
                 )
                     .into_response();
             }
+            AppError::InvalidPassword => (StatusCode::UNAUTHORIZED, "invalid_password", None),
+            AppError::InvalidAlias(msg) => {
+                (StatusCode::BAD_REQUEST, "invalid_alias", Some(msg.clone()))
+            }
+            AppError::InvalidTtl(msg) => {
+                (StatusCode::BAD_REQUEST, "invalid_ttl", Some(msg.clone()))
+            }
+            AppError::ShortCodeTaken(code) => (
+                StatusCode::CONFLICT,
+                "short_code_taken",
+                Some(format!("Short code '{}' is already in use", code)),
+            ),
             AppError::AdminRightsRequired => (StatusCode::FORBIDDEN, "admin_rights_required", None),
+            AppError::InvalidApiKey => (StatusCode::UNAUTHORIZED, "invalid_api_key", None),
+            AppError::InsufficientScope(scope) => (
+                StatusCode::FORBIDDEN,
+                "insufficient_scope",
+                Some(format!("Missing scope '{}'", scope)),
+            ),
+            AppError::ApiKeyNotFound => (StatusCode::NOT_FOUND, "not_found", None),
+            AppError::InvalidRequest(msg) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_request",
+                Some(msg.clone()),
+            ),
             AppError::ShortCodeExhausted => (
                 StatusCode::SERVICE_UNAVAILABLE,
                 "short_code_exhausted",
@@ -74,6 +153,11 @@ impl IntoResponse for AppError {
                 tracing::error!("Database error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", None)
             }
+            AppError::InvalidQrStyle(msg) => (
+                StatusCode::BAD_REQUEST,
+                "invalid_qr_style",
+                Some(msg.clone()),
+            ),
             AppError::QrGeneration(msg) => {
                 tracing::error!("QR generation error: {}", msg);
                 (
@@ -82,6 +166,18 @@ impl IntoResponse for AppError {
                     Some(msg.clone()),
                 )
             }
+            AppError::QrRenderBusy => {
+                let body = ErrorResponse {
+                    error: "qr_busy".to_string(),
+                    message: Some(format!("Retry after {} seconds", QR_RENDER_RETRY_AFTER)),
+                };
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    [(header::RETRY_AFTER, QR_RENDER_RETRY_AFTER.to_string())],
+                    Json(body),
+                )
+                    .into_response();
+            }
             AppError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", None)
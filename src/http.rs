@@ -1,6 +1,14 @@
+#[path = "http/auth.rs"]
+mod auth;
 #[path = "http/handlers.rs"]
 mod handlers;
+#[path = "http/holding.rs"]
+mod holding;
+#[path = "http/preview.rs"]
+mod preview;
 #[path = "http/router.rs"]
 pub mod router;
+#[path = "http/unlock.rs"]
+mod unlock;
 
-pub use router::create_router;
+pub use router::{AppState, create_router};
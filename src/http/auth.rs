@@ -0,0 +1,196 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderMap, header, request::Parts},
+};
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::domain::Scope;
+use crate::error::{AppError, AppResult};
+use crate::http::router::AppState;
+
+/// Header carrying the admin secret.
+const ADMIN_SECRET_HEADER: &str = "x-admin-secret";
+
+/// Alternative header carrying an API key (besides `Authorization: Bearer`).
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// The caller of an authorized request.
+#[derive(Debug, Clone)]
+pub enum Principal {
+    /// No credentials, on an endpoint that does not require them
+    Anonymous,
+    /// Caller presented the admin secret
+    Admin,
+    /// Caller presented an API key
+    ApiKey { id: Uuid, name: String },
+}
+
+impl std::fmt::Display for Principal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Principal::Anonymous => write!(f, "anonymous"),
+            Principal::Admin => write!(f, "admin"),
+            Principal::ApiKey { id, name } => write!(f, "api key '{}' ({})", name, id),
+        }
+    }
+}
+
+/// Type-level scope marker for the [`Authorized`] extractor.
+pub trait RequiredScope: Send + Sync + 'static {
+    const SCOPE: Scope;
+}
+
+/// Scope markers, one per [`Scope`] variant.
+pub mod scope {
+    use super::{RequiredScope, Scope};
+
+    macro_rules! scope_marker {
+        ($name:ident) => {
+            pub struct $name;
+
+            impl RequiredScope for $name {
+                const SCOPE: Scope = Scope::$name;
+            }
+        };
+    }
+
+    scope_marker!(LinksCreate);
+    scope_marker!(LinksRead);
+    scope_marker!(LinksUpdate);
+    scope_marker!(LinksDelete);
+    scope_marker!(QrGenerate);
+}
+
+/// Extractor that admits a request if it carries the admin secret or an API key
+/// granting scope `S`. Endpoints are open when their protection is not configured:
+/// management scopes are protected once `ADMIN_SECRET` is set, creation scopes
+/// once `REQUIRE_API_KEY` is enabled.
+pub struct Authorized<S: RequiredScope> {
+    pub principal: Principal,
+    _scope: PhantomData<S>,
+}
+
+impl<S: RequiredScope> FromRequestParts<AppState> for Authorized<S> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> AppResult<Self> {
+        let principal = authorize(&parts.headers, state, S::SCOPE).await?;
+        Ok(Authorized {
+            principal,
+            _scope: PhantomData,
+        })
+    }
+}
+
+/// Extractor that only admits the admin secret (used for API key management).
+/// Open when no `ADMIN_SECRET` is configured, like other admin endpoints.
+pub struct AdminOnly;
+
+impl FromRequestParts<AppState> for AdminOnly {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> AppResult<Self> {
+        if let Some(required_secret) = &state.admin_secret
+            && !header_matches_secret(&parts.headers, required_secret)
+        {
+            return Err(AppError::AdminRightsRequired);
+        }
+        Ok(AdminOnly)
+    }
+}
+
+async fn authorize(headers: &HeaderMap, state: &AppState, scope: Scope) -> AppResult<Principal> {
+    if let Some(required_secret) = &state.admin_secret
+        && headers.contains_key(ADMIN_SECRET_HEADER)
+    {
+        if header_matches_secret(headers, required_secret) {
+            return Ok(Principal::Admin);
+        }
+        return Err(AppError::AdminRightsRequired);
+    }
+
+    if let Some(key) = api_key_from_headers(headers) {
+        let api_key = state.api_key_service.authenticate(key).await?;
+        if !api_key.has_scope(scope) {
+            return Err(AppError::InsufficientScope(scope));
+        }
+        return Ok(Principal::ApiKey {
+            id: api_key.id,
+            name: api_key.name,
+        });
+    }
+
+    let protected = match scope {
+        Scope::LinksCreate | Scope::QrGenerate => state.require_api_key,
+        Scope::LinksRead | Scope::LinksUpdate | Scope::LinksDelete => state.admin_secret.is_some(),
+    };
+
+    if protected {
+        return Err(AppError::AdminRightsRequired);
+    }
+
+    Ok(Principal::Anonymous)
+}
+
+/// Compare the admin secret header with the configured secret in constant time.
+/// Both sides are hashed first so the comparison does not leak the secret's length.
+fn header_matches_secret(headers: &HeaderMap, required_secret: &str) -> bool {
+    let Some(provided) = headers
+        .get(ADMIN_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    let provided = Sha256::digest(provided.as_bytes());
+    let required = Sha256::digest(required_secret.as_bytes());
+    provided.ct_eq(&required).into()
+}
+
+/// Read an API key from `Authorization: Bearer <key>` or `X-Api-Key`.
+fn api_key_from_headers(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    bearer.or_else(|| {
+        headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_matches_secret() {
+        let mut headers = HeaderMap::new();
+        assert!(!header_matches_secret(&headers, "s3cret"));
+
+        headers.insert(ADMIN_SECRET_HEADER, "wrong".parse().unwrap());
+        assert!(!header_matches_secret(&headers, "s3cret"));
+
+        headers.insert(ADMIN_SECRET_HEADER, "s3cret".parse().unwrap());
+        assert!(header_matches_secret(&headers, "s3cret"));
+    }
+
+    #[test]
+    fn test_api_key_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(api_key_from_headers(&headers), None);
+
+        headers.insert(API_KEY_HEADER, "qrl_a".parse().unwrap());
+        assert_eq!(api_key_from_headers(&headers), Some("qrl_a"));
+
+        headers.insert(header::AUTHORIZATION, "Bearer qrl_b".parse().unwrap());
+        assert_eq!(api_key_from_headers(&headers), Some("qrl_b"));
+    }
+}
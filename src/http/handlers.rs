@@ -1,17 +1,44 @@
 use axum::{
     Json,
-    extract::{ConnectInfo, Path, State},
-    http::{HeaderMap, StatusCode, header},
-    response::{IntoResponse, Redirect, Response},
+    extract::{ConnectInfo, Form, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, Uri, header},
+    response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::net::SocketAddr;
 use url::Url;
 use uuid::Uuid;
 
-use crate::domain::{LinkResponse, Ttl};
+use crate::domain::{
+    ApiKeyResponse, CampaignStats, DEFAULT_PAGE_SIZE, Link, LinkCursor, LinkPage, LinkQuery,
+    LinkResponse, LinkSort, LinkStats, LinkStatus, Passthrough, RedirectRule, RedirectType, Scope,
+    SortOrder, Ttl, Utm, Variant, Visitor, pick_variant,
+};
 use crate::error::{AppError, AppResult};
+use crate::http::auth::{AdminOnly, Authorized, scope};
 use crate::http::router::AppState;
+use crate::http::{holding, preview, unlock};
+use crate::metrics;
+use crate::qr::{QrColor, QrEcLevel, QrEyeShape, QrFormat, QrGradient, QrModuleShape, QrStyle};
+use crate::service::{LinkUpdate, NewLink};
+
+/// Cookie keeping a visitor on the same variant of a split link, scoped to its short code.
+const VARIANT_COOKIE: &str = "qrlink_variant";
+
+/// Lifetime of the variant cookie (30 days).
+const VARIANT_COOKIE_MAX_AGE: u32 = 30 * 24 * 3600;
+
+/// Time browsers and CDNs may cache a link's QR code image, in seconds (1 day).
+/// The image only depends on the short URL and rendering options, and revalidates by ETag.
+const QR_IMAGE_MAX_AGE: u32 = 24 * 3600;
+
+/// Cache-Control of cacheable permanent redirects.
+/// Without a limit browsers keep 308s forever, but links can be edited at any time:
+/// an hour is a deliberate trade-off, letting repeat visits skip the server while
+/// edits reach every browser within the hour.
+const PERMANENT_REDIRECT_CACHE_CONTROL: &str = "private, max-age=3600";
 
 /// Request body for creating a new link.
 #[derive(Debug, Deserialize)]
@@ -19,12 +46,189 @@ pub struct CreateLinkRequest {
     pub url: String,
     #[serde(default)]
     pub ttl: Option<Ttl>,
+    /// Optional custom short code (e.g., "spring-sale")
+    #[serde(default)]
+    pub alias: Option<String>,
+    /// Optional password required to follow the link
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Optional number of redirects before the link is used up (1 for single-use)
+    #[serde(default)]
+    pub max_clicks: Option<u32>,
+    /// Optional launch time; until then the link shows a holding page
+    #[serde(default)]
+    pub active_from: Option<DateTime<Utc>>,
+    /// Optional URL to redirect to before `active_from`, instead of the holding page
+    #[serde(default)]
+    pub prelaunch_url: Option<String>,
+    /// Optional weighted destinations to split the traffic across (A/B testing)
+    #[serde(default)]
+    pub variants: Vec<Variant>,
+    /// Optional redirect status instead of the server default
+    #[serde(default)]
+    pub redirect_type: Option<RedirectType>,
+    /// Optional forwarding of extra path segments and the query to the destination
+    #[serde(default)]
+    pub passthrough: Passthrough,
+    /// Optional campaign tracking parameters, added to the target URL
+    #[serde(default)]
+    pub utm: Utm,
+    /// Show visitors a preview page with a continue button instead of redirecting
+    #[serde(default)]
+    pub interstitial: bool,
+}
+
+/// Request body for updating an existing link.
+/// Omitted fields are left unchanged.
+#[derive(Debug, Deserialize)]
+pub struct UpdateLinkRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub ttl: Option<Ttl>,
+    #[serde(default)]
+    pub redirect_type: Option<RedirectType>,
+    #[serde(default)]
+    pub passthrough: Option<Passthrough>,
+    #[serde(default)]
+    pub interstitial: Option<bool>,
+    #[serde(default)]
+    pub utm: Option<Utm>,
+}
+
+/// Request body for replacing the A/B split destinations of a link.
+#[derive(Debug, Deserialize)]
+pub struct SetVariantsRequest {
+    pub variants: Vec<Variant>,
+}
+
+/// Redirect rules of a link, in evaluation order; used as request and response body.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedirectRules {
+    pub rules: Vec<RedirectRule>,
+}
+
+/// Query parameters for listing links.
+#[derive(Debug, Default, Deserialize)]
+pub struct ListLinksParams {
+    pub status: Option<LinkStatus>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Target domain, matching its subdomains too
+    pub domain: Option<String>,
+    /// Short code prefix (case-sensitive)
+    pub prefix: Option<String>,
+    /// UTM campaign (exact match)
+    pub campaign: Option<String>,
+    #[serde(default)]
+    pub sort: LinkSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub limit: Option<u32>,
+    /// Opaque cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
+}
+
+impl ListLinksParams {
+    /// Convert into a link query, decoding the cursor.
+    pub fn into_query(self) -> AppResult<LinkQuery> {
+        let cursor = self
+            .cursor
+            .as_deref()
+            .map(LinkCursor::decode)
+            .transpose()
+            .map_err(AppError::InvalidRequest)?;
+
+        Ok(LinkQuery {
+            status: self.status,
+            created_after: self.created_after,
+            created_before: self.created_before,
+            domain: self.domain,
+            short_code_prefix: self.prefix,
+            campaign: self.campaign,
+            sort: self.sort,
+            order: self.order,
+            limit: self.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+            cursor,
+        })
+    }
+}
+
+/// Form body for unlocking a password-protected link, or continuing past an
+/// interstitial page (without a password).
+#[derive(Debug, Deserialize)]
+pub struct UnlockForm {
+    #[serde(default)]
+    pub password: String,
 }
 
 /// Request body for generating a QR code from a raw URL.
 #[derive(Debug, Deserialize)]
 pub struct CreateQrRequest {
     pub url: String,
+    /// Output format; falls back to the `Accept` header, then PNG
+    #[serde(default)]
+    pub format: Option<QrFormat>,
+    #[serde(flatten)]
+    pub style: QrStyleParams,
+}
+
+/// Optional per-request QR styling; unset fields use the server defaults.
+#[derive(Debug, Default, Deserialize)]
+pub struct QrStyleParams {
+    /// Dark module color (`#rrggbb`)
+    pub foreground: Option<QrColor>,
+    /// Gradient filling dark modules in place of the foreground color
+    pub gradient: Option<QrGradient>,
+    /// Light module and quiet zone color (`#rrggbb`)
+    pub background: Option<QrColor>,
+    /// Image size in pixels
+    pub size: Option<u32>,
+    /// Quiet zone width in modules
+    pub quiet_zone: Option<u32>,
+    /// Error correction level (`L`, `M`, `Q` or `H`)
+    pub ec_level: Option<QrEcLevel>,
+    /// Set to false to omit the branding logo
+    pub logo: Option<bool>,
+    /// Data module shape (`square`, `dot` or `rounded`)
+    pub module_shape: Option<QrModuleShape>,
+    /// Shape of the finder pattern rings (`square`, `rounded` or `circle`)
+    pub eye_outer_shape: Option<QrEyeShape>,
+    /// Shape of the finder pattern centers (`square`, `rounded` or `circle`)
+    pub eye_inner_shape: Option<QrEyeShape>,
+    /// Finder pattern ring color (`#rrggbb`), defaults to the foreground
+    pub eye_outer_color: Option<QrColor>,
+    /// Finder pattern center color (`#rrggbb`), defaults to the foreground
+    pub eye_inner_color: Option<QrColor>,
+}
+
+impl QrStyleParams {
+    /// Apply the requested overrides on top of a base style.
+    pub fn apply(&self, base: QrStyle) -> QrStyle {
+        QrStyle {
+            foreground: self.foreground.unwrap_or(base.foreground),
+            gradient: self.gradient.clone().or(base.gradient),
+            background: self.background.unwrap_or(base.background),
+            size: self.size.unwrap_or(base.size),
+            quiet_zone: self.quiet_zone.unwrap_or(base.quiet_zone),
+            ec_level: self.ec_level.unwrap_or(base.ec_level),
+            logo: self.logo.unwrap_or(base.logo),
+            module_shape: self.module_shape.unwrap_or(base.module_shape),
+            eye_outer_shape: self.eye_outer_shape.unwrap_or(base.eye_outer_shape),
+            eye_inner_shape: self.eye_inner_shape.unwrap_or(base.eye_inner_shape),
+            eye_outer_color: self.eye_outer_color.or(base.eye_outer_color),
+            eye_inner_color: self.eye_inner_color.or(base.eye_inner_color),
+        }
+    }
+}
+
+/// Query options for the QR code image of a link.
+#[derive(Debug, Default, Deserialize)]
+pub struct QrImageParams {
+    /// Output format; falls back to the `Accept` header, then PNG
+    pub format: Option<QrFormat>,
+    /// Image size in pixels
+    pub size: Option<u32>,
 }
 
 /// Response for creating a new link.
@@ -34,11 +238,30 @@ pub struct CreateLinkResponse {
     pub link: LinkResponse,
 }
 
+/// Request body for issuing a new API key.
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response for issuing a new API key. The key is only ever shown here.
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    pub key: String,
+}
+
 /// Handler for creating a new short link.
 /// POST /api/v1/links
+/// Requires `links:create` if API keys are enforced.
 pub async fn create_link(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    _auth: Authorized<scope::LinksCreate>,
     Json(req): Json<CreateLinkRequest>,
 ) -> AppResult<(StatusCode, Json<CreateLinkResponse>)> {
     // Rate limiting by IP
@@ -46,16 +269,37 @@ pub async fn create_link(
         return Err(AppError::RateLimitExceeded(retry_after));
     }
 
-    let link = state.link_service.create_link(&req.url, req.ttl).await?;
+    let link = state
+        .link_service
+        .create_link(
+            &req.url,
+            NewLink {
+                ttl: req.ttl,
+                alias: req.alias,
+                password: req.password,
+                max_clicks: req.max_clicks,
+                active_from: req.active_from,
+                prelaunch_url: req.prelaunch_url,
+                variants: req.variants,
+                redirect_type: req.redirect_type,
+                passthrough: req.passthrough,
+                utm: req.utm,
+                interstitial: req.interstitial,
+            },
+        )
+        .await?;
 
     Ok((StatusCode::CREATED, Json(CreateLinkResponse { link })))
 }
 
 /// Handler for generating a QR code from a raw URL (no DB, no shortening).
 /// POST /api/v1/qr
+/// Requires `qr:generate` if API keys are enforced.
 pub async fn create_qr(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    _auth: Authorized<scope::QrGenerate>,
+    headers: HeaderMap,
     Json(req): Json<CreateQrRequest>,
 ) -> AppResult<Response> {
     // Rate limiting by IP
@@ -66,67 +310,571 @@ pub async fn create_qr(
     let url =
         Url::parse(&req.url).map_err(|e| AppError::InvalidUrl(format!("{}: {}", e, req.url)))?;
 
-    let png_data = state.qr_service.generate_for_url(url.as_str())?;
+    let format = req
+        .format
+        .or_else(|| header_str(&headers, header::ACCEPT).and_then(QrFormat::from_accept))
+        .unwrap_or_default();
+
+    let style = req.style.apply(state.qr_service.default_style());
+
+    let data = state
+        .qr_service
+        .generate_for_url(url.as_str(), format, &style)
+        .await?;
 
     Ok((
         StatusCode::OK,
-        [(header::CONTENT_TYPE, "image/png")],
-        png_data,
+        [(header::CONTENT_TYPE, format.content_type())],
+        data,
     )
         .into_response())
 }
 
-/// Handler for listing all links.
-/// GET /api/v1/links
-/// Requires admin secret if configured.
-pub async fn list_links(
+/// Handler for the QR code image of a short link, for embedding in pages and emails.
+/// GET /:short_code/qr
+/// Public, like the short link itself; rate limited by IP.
+pub async fn short_link_qr(
     State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(short_code): Path<String>,
     headers: HeaderMap,
-) -> AppResult<Json<Vec<LinkResponse>>> {
-    if let Some(required_secret) = &state.admin_secret {
-        let provided = headers
-            .get("x-admin-secret")
-            .and_then(|value| value.to_str().ok());
-
-        if provided != Some(required_secret.as_str()) {
-            return Err(AppError::AdminRightsRequired);
-        }
+    Query(params): Query<QrImageParams>,
+) -> AppResult<Response> {
+    if let Err(retry_after) = state.rate_limiter.check(addr.ip()).await {
+        return Err(AppError::RateLimitExceeded(retry_after));
+    }
+
+    let link = state.link_service.get_by_short_code(&short_code).await?;
+    qr_image(&state, &link, &params, &headers, "public").await
+}
+
+/// Handler for the QR code image of a link, by ID.
+/// GET /api/v1/links/:id/qr
+/// Requires admin secret or `links:read` if admin secret is configured.
+pub async fn link_qr(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(id): Path<Uuid>,
+    _auth: Authorized<scope::LinksRead>,
+    headers: HeaderMap,
+    Query(params): Query<QrImageParams>,
+) -> AppResult<Response> {
+    if let Err(retry_after) = state.rate_limiter.check(addr.ip()).await {
+        return Err(AppError::RateLimitExceeded(retry_after));
+    }
+
+    let link = state.link_service.get_link(id).await?;
+    qr_image(&state, &link, &params, &headers, "private").await
+}
+
+/// Render a link's QR code as a cacheable image response, answering
+/// `304 Not Modified` when the client already has it.
+/// `visibility` is the Cache-Control directive allowing (`public`) or preventing
+/// (`private`) shared caches from storing it.
+async fn qr_image(
+    state: &AppState,
+    link: &LinkResponse,
+    params: &QrImageParams,
+    headers: &HeaderMap,
+    visibility: &str,
+) -> AppResult<Response> {
+    let format = params
+        .format
+        .or_else(|| header_str(headers, header::ACCEPT).and_then(QrFormat::from_accept))
+        .unwrap_or_default();
+    let style = QrStyleParams {
+        size: params.size,
+        ..QrStyleParams::default()
+    }
+    .apply(state.qr_service.default_style());
+
+    let data = state
+        .qr_service
+        .generate_for_url(&link.short_url, format, &style)
+        .await?;
+
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&data)[..16]));
+    let cache_control = format!("{}, max-age={}", visibility, QR_IMAGE_MAX_AGE);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, cache_control),
+        (header::VARY, "Accept".to_string()),
+    ];
+
+    let not_modified = header_str(headers, header::IF_NONE_MATCH).is_some_and(|tags| {
+        tags.split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag || tag == "*")
+    });
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
     }
 
-    let links = state.link_service.list_all().await?;
-    Ok(Json(links))
+    Ok((
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, format.content_type())],
+        data,
+    )
+        .into_response())
+}
+
+/// Handler for listing links, one page at a time.
+/// GET /api/v1/links
+/// Requires admin secret or `links:read` if admin secret is configured.
+pub async fn list_links(
+    State(state): State<AppState>,
+    _auth: Authorized<scope::LinksRead>,
+    Query(params): Query<ListLinksParams>,
+) -> AppResult<Json<LinkPage>> {
+    let page = state.link_service.list_links(params.into_query()?).await?;
+    Ok(Json(page))
+}
+
+/// Handler for updating a link's target URL and/or expiry.
+/// PATCH /api/v1/links/:id
+/// Requires admin secret or `links:update` if admin secret is configured.
+pub async fn update_link(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    auth: Authorized<scope::LinksUpdate>,
+    Json(req): Json<UpdateLinkRequest>,
+) -> AppResult<Json<LinkResponse>> {
+    let link = state
+        .link_service
+        .update_link(
+            id,
+            LinkUpdate {
+                url: req.url,
+                ttl: req.ttl,
+                redirect_type: req.redirect_type,
+                passthrough: req.passthrough,
+                interstitial: req.interstitial,
+                utm: req.utm,
+            },
+        )
+        .await?;
+
+    tracing::info!("Link {} updated by {}", id, auth.principal);
+    Ok(Json(link))
+}
+
+/// Handler for replacing a link's A/B split destinations.
+/// PUT /api/v1/links/:id/variants
+/// Requires admin secret or `links:update` if admin secret is configured.
+pub async fn set_link_variants(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    auth: Authorized<scope::LinksUpdate>,
+    Json(req): Json<SetVariantsRequest>,
+) -> AppResult<Json<LinkResponse>> {
+    let link = state.link_service.set_variants(id, req.variants).await?;
+
+    tracing::info!("Variants of link {} updated by {}", id, auth.principal);
+    Ok(Json(link))
 }
 
 /// Handler for deleting a link.
 /// DELETE /api/v1/links/:id
-/// Requires admin secret if configured.
+/// Requires admin secret or `links:delete` if admin secret is configured.
 pub async fn delete_link(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-    headers: HeaderMap,
+    auth: Authorized<scope::LinksDelete>,
 ) -> AppResult<StatusCode> {
-    if let Some(required_secret) = &state.admin_secret {
-        let provided = headers
-            .get("x-admin-secret")
-            .and_then(|value| value.to_str().ok());
+    state.link_service.delete_link(id).await?;
 
-        if provided != Some(required_secret.as_str()) {
-            return Err(AppError::AdminRightsRequired);
-        }
-    }
+    tracing::info!("Link {} deleted by {}", id, auth.principal);
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    state.link_service.delete_link(id).await?;
+/// Handler for reading a link's click statistics.
+/// GET /api/v1/links/:id/stats
+/// Requires admin secret or `links:read` if admin secret is configured.
+pub async fn link_stats(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    _auth: Authorized<scope::LinksRead>,
+) -> AppResult<Json<LinkStats>> {
+    // Ensure the link exists so unknown IDs yield 404 rather than empty stats
+    state.link_service.get_link(id).await?;
+
+    let stats = state.analytics_service.stats(id).await?;
+    Ok(Json(stats))
+}
+
+/// Handler for click statistics across all links of a UTM campaign.
+/// GET /api/v1/campaigns/:campaign/stats
+/// Requires admin secret or `links:read` if admin secret is configured.
+pub async fn campaign_stats(
+    State(state): State<AppState>,
+    Path(campaign): Path<String>,
+    _auth: Authorized<scope::LinksRead>,
+) -> AppResult<Json<CampaignStats>> {
+    let stats = state.analytics_service.campaign_stats(&campaign).await?;
+    Ok(Json(stats))
+}
+
+/// Handler for reading a link's redirect rules.
+/// GET /api/v1/links/:id/rules
+/// Requires admin secret or `links:read` if admin secret is configured.
+pub async fn get_link_rules(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    _auth: Authorized<scope::LinksRead>,
+) -> AppResult<Json<RedirectRules>> {
+    let rules = state.redirect_rule_service.get_rules(id).await?;
+    Ok(Json(RedirectRules { rules }))
+}
+
+/// Handler for replacing a link's redirect rules.
+/// PUT /api/v1/links/:id/rules
+/// Requires admin secret or `links:update` if admin secret is configured.
+pub async fn set_link_rules(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    auth: Authorized<scope::LinksUpdate>,
+    Json(req): Json<RedirectRules>,
+) -> AppResult<Json<RedirectRules>> {
+    let rules = state.redirect_rule_service.set_rules(id, req.rules).await?;
+
+    tracing::info!(
+        "Redirect rules of link {} updated by {}",
+        id,
+        auth.principal
+    );
+    Ok(Json(RedirectRules { rules }))
+}
+
+/// Handler for issuing a new API key.
+/// POST /api/v1/keys
+/// Requires admin secret if configured.
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    _admin: AdminOnly,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> AppResult<(StatusCode, Json<CreateApiKeyResponse>)> {
+    let (api_key, key) = state
+        .api_key_service
+        .create_key(&req.name, req.scopes, req.expires_at)
+        .await?;
+
+    tracing::info!("API key '{}' ({}) created", api_key.name, api_key.id);
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateApiKeyResponse { api_key, key }),
+    ))
+}
+
+/// Handler for listing API keys.
+/// GET /api/v1/keys
+/// Requires admin secret if configured.
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    _admin: AdminOnly,
+) -> AppResult<Json<Vec<ApiKeyResponse>>> {
+    let keys = state.api_key_service.list_keys().await?;
+    Ok(Json(keys))
+}
+
+/// Handler for revoking an API key.
+/// DELETE /api/v1/keys/:id
+/// Requires admin secret if configured.
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    _admin: AdminOnly,
+) -> AppResult<StatusCode> {
+    state.api_key_service.revoke_key(id).await?;
+
+    tracing::info!("API key {} revoked", id);
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// Handler for redirecting to a short link.
 /// GET /:short_code
+/// Password-protected links get an unlock form instead.
 pub async fn redirect(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(short_code): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Result<Response, AppError> {
+    redirect_link(&state, addr, &short_code, false, &headers, &uri).await
+}
+
+/// Handler for a short link followed by extra path segments.
+/// GET /:short_code/*rest
+/// Only links with passthrough enabled are found; the rest is forwarded.
+pub async fn redirect_with_path(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path((short_code, _rest)): Path<(String, String)>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Result<Response, AppError> {
+    redirect_link(&state, addr, &short_code, true, &headers, &uri).await
+}
+
+async fn redirect_link(
+    state: &AppState,
+    addr: SocketAddr,
+    short_code: &str,
+    with_suffix: bool,
+    headers: &HeaderMap,
+    uri: &Uri,
+) -> Result<Response, AppError> {
+    let link = match resolve_for_redirect(state, short_code, with_suffix).await {
+        Err(AppError::LinkNotActive {
+            active_from,
+            prelaunch_url,
+        }) => return Ok(holding::respond(active_from, prelaunch_url.as_ref())),
+        outcome => outcome?,
+    };
+
+    if link.is_protected() {
+        return Ok(unlock::page(StatusCode::OK, link.short_code.as_str(), None));
+    }
+    if link.interstitial {
+        return Ok(preview_page(state, &state.link_service.to_response(&link)).await);
+    }
+
+    let redirect_type = link.redirect_type.unwrap_or(state.default_redirect_type);
+    follow(state, &link, addr, headers, uri, redirect_type).await
+}
+
+/// Handler for the unlock form of a password-protected link, and the continue
+/// button of an interstitial page.
+/// POST /:short_code
+/// Failed attempts are rate limited per IP and per link.
+pub async fn unlock(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(short_code): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+    Form(form): Form<UnlockForm>,
+) -> Result<Response, AppError> {
+    let link = match resolve_for_redirect(&state, &short_code, false).await {
+        Err(AppError::LinkNotActive {
+            active_from,
+            prelaunch_url,
+        }) => return Ok(holding::respond(active_from, prelaunch_url.as_ref())),
+        outcome => outcome?,
+    };
+    let link_id = link.id;
+    let code = link.short_code.as_str().to_string();
+
+    let limited = match state.unlock_ip_limiter.peek(&addr.ip()).await {
+        Ok(_) => state.unlock_link_limiter.peek(&link_id).await,
+        Err(retry_after) => Err(retry_after),
+    };
+    if let Err(retry_after) = limited {
+        let mut response = unlock::page(
+            StatusCode::TOO_MANY_REQUESTS,
+            &code,
+            Some(&format!(
+                "Too many attempts. Try again in {} seconds.",
+                retry_after
+            )),
+        );
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, retry_after.into());
+        return Ok(response);
+    }
+
+    let link = match state.link_service.check_password(link, form.password).await {
+        Ok(link) => link,
+        Err(AppError::InvalidPassword) => {
+            let _ = state.unlock_ip_limiter.check(addr.ip()).await;
+            let _ = state.unlock_link_limiter.check(link_id).await;
+            return Ok(unlock::page(
+                StatusCode::UNAUTHORIZED,
+                &code,
+                Some("Incorrect password."),
+            ));
+        }
+        Err(e) => return Err(e),
+    };
+
+    // 303 so the browser follows with a GET
+    follow(&state, &link, addr, &headers, &uri, RedirectType::SeeOther).await
+}
+
+/// Handler for the preview page of a short link: its destination, dates and
+/// QR code, with a button to continue. Does not count as a redirect.
+/// GET /:short_code/preview
+pub async fn preview(
     State(state): State<AppState>,
     Path(short_code): Path<String>,
 ) -> Result<Response, AppError> {
-    let link = state.link_service.resolve(&short_code).await?;
-    Ok(Redirect::temporary(link.target_url.as_str()).into_response())
+    let link = state.link_service.get_by_short_code(&short_code).await?;
+    Ok(preview_page(&state, &link).await)
+}
+
+/// Render the preview page with the link's QR code. A QR rendering failure only
+/// leaves out the image.
+async fn preview_page(state: &AppState, link: &LinkResponse) -> Response {
+    let qr = state
+        .qr_service
+        .generate_for_url(
+            &link.short_url,
+            QrFormat::Svg,
+            &state.qr_service.default_style(),
+        )
+        .await
+        .inspect_err(|e| tracing::warn!("Preview QR code for {} failed: {}", link.short_code, e))
+        .ok();
+    preview::page(link, qr.as_deref())
+}
+
+/// Resolve a short code for redirection, counting not-found, expired, exhausted
+/// and scheduled outcomes.
+async fn resolve_for_redirect(
+    state: &AppState,
+    short_code: &str,
+    with_suffix: bool,
+) -> AppResult<Link> {
+    let outcome = if with_suffix {
+        state.link_service.resolve_with_suffix(short_code).await
+    } else {
+        state.link_service.resolve(short_code).await
+    };
+    let label = match &outcome {
+        Err(AppError::LinkNotFound) => Some("not_found"),
+        Err(AppError::LinkExpired) => Some("expired"),
+        Err(AppError::LinkExhausted) => Some("exhausted"),
+        Err(AppError::LinkNotActive { .. }) => Some("scheduled"),
+        _ => None,
+    };
+    if let Some(label) = label {
+        metrics::REDIRECTS.with_label_values(&[label]).inc();
+    }
+    outcome
+}
+
+/// Record a click and redirect to the link's destination for the visitor:
+/// its first matching redirect rule, else its variant for split links, else its target.
+/// Visitors drawn a variant keep it through a cookie.
+/// With passthrough, the request's path after the short code and its query are forwarded.
+async fn follow(
+    state: &AppState,
+    link: &Link,
+    addr: SocketAddr,
+    headers: &HeaderMap,
+    uri: &Uri,
+    redirect_type: RedirectType,
+) -> AppResult<Response> {
+    let now = Utc::now();
+    let visitor = Visitor::from_headers(
+        header_str(headers, header::USER_AGENT),
+        header_str(headers, header::ACCEPT_LANGUAGE),
+        now,
+    );
+    let routing = state.redirect_rule_service.route(link, &visitor).await?;
+    let rule_destination = routing.destination;
+
+    let sticky = cookie_value(headers, VARIANT_COOKIE);
+    let variant = match rule_destination {
+        Some(_) => None,
+        None => pick_variant(&link.variants, sticky, rand::random()),
+    };
+    let destination = rule_destination
+        .as_ref()
+        .or(variant.map(|v| &v.url))
+        .unwrap_or(&link.target_url);
+    let suffix = uri
+        .path()
+        .trim_start_matches('/')
+        .split_once('/')
+        .map_or("", |(_, rest)| rest);
+    let destination = link.passthrough.apply(destination, suffix, uri.query());
+
+    state.analytics_service.record(
+        link.id,
+        addr.ip(),
+        header_str(headers, header::REFERER),
+        header_str(headers, header::USER_AGENT),
+        variant.map(|v| v.name.as_str()),
+    );
+    metrics::REDIRECTS.with_label_values(&["ok"]).inc();
+
+    let location = HeaderValue::from_str(destination.as_str())
+        .map_err(|e| AppError::Internal(format!("Invalid redirect location: {}", e)))?;
+    // Visitors may get another destination, even when no rule matched this one
+    let per_visitor = routing.has_rules || !link.variants.is_empty();
+    let mut response = (
+        StatusCode::from_u16(redirect_type.status_code()).unwrap_or(StatusCode::TEMPORARY_REDIRECT),
+        [
+            (header::LOCATION, location),
+            (
+                header::CACHE_CONTROL,
+                redirect_cache_control(link, redirect_type, per_visitor),
+            ),
+        ],
+    )
+        .into_response();
+    if let Some(variant) = variant
+        && sticky != Some(variant.name.as_str())
+    {
+        let cookie = format!(
+            "{}={}; Path=/{}; Max-Age={}; HttpOnly; SameSite=Lax",
+            VARIANT_COOKIE, variant.name, link.short_code, VARIANT_COOKIE_MAX_AGE
+        );
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    Ok(response)
+}
+
+/// Cache-Control of a redirect. Only permanent redirects of links that neither expire
+/// nor depend on the visitor or a click limit may be cached, for a bounded time;
+/// other redirects are not cached, so every visit reaches the server and is counted.
+fn redirect_cache_control(
+    link: &Link,
+    redirect_type: RedirectType,
+    per_visitor: bool,
+) -> HeaderValue {
+    let cacheable = redirect_type.is_permanent()
+        && !per_visitor
+        && link.max_clicks.is_none()
+        && link.expires_at.is_none();
+
+    if cacheable {
+        HeaderValue::from_static(PERMANENT_REDIRECT_CACHE_CONTROL)
+    } else {
+        HeaderValue::from_static("no-store")
+    }
+}
+
+/// Get the value of a request cookie.
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|cookie| {
+            let (key, value) = cookie.trim().split_once('=')?;
+            (key == name).then_some(value)
+        })
+}
+
+/// Get a header value as a string slice, ignoring non-UTF-8 values.
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Prometheus metrics endpoint.
+/// GET /metrics
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
 }
 
 /// Health check endpoint.
@@ -0,0 +1,71 @@
+use axum::{
+    http::{HeaderValue, StatusCode, header},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use chrono::{DateTime, Utc};
+use url::Url;
+
+/// Respond to a visit of a link before its activation: redirect to the
+/// pre-launch URL if it has one, or show a holding page.
+pub fn respond(active_from: DateTime<Utc>, prelaunch_url: Option<&Url>) -> Response {
+    let mut response = match prelaunch_url {
+        Some(url) => Redirect::temporary(url.as_str()).into_response(),
+        None => page(active_from),
+    };
+
+    // The response changes at launch, so it must not be cached
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// Holding page, styled like the web UI.
+fn page(active_from: DateTime<Utc>) -> Response {
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>Coming soon</title>
+<link rel="stylesheet" href="/styles.css">
+</head>
+<body>
+<header class="header">
+<div class="header-left"><span class="logo">QRLink</span></div>
+</header>
+<main class="main">
+<div class="card">
+<h1 class="label">Coming soon</h1>
+<p>This link opens on <time datetime="{iso}">{display}</time>.</p>
+</div>
+</main>
+</body>
+</html>
+"#,
+        iso = active_from.to_rfc3339(),
+        display = active_from.format("%B %-d, %Y at %H:%M UTC"),
+    );
+
+    (StatusCode::OK, Html(html)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respond_redirects_to_prelaunch_url() {
+        let url = Url::parse("https://example.com/teaser").unwrap();
+        let response = respond(Utc::now(), Some(&url));
+
+        assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "https://example.com/teaser"
+        );
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+    }
+}
@@ -0,0 +1,164 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Response},
+};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+
+use crate::domain::LinkResponse;
+use crate::http::unlock::escape;
+
+/// Render the preview page of a link: its destination, dates and QR code (an SVG
+/// image, if rendered), with a button to continue to the destination.
+/// The destination of a password-protected link, or of a link that has not
+/// opened yet, is not shown.
+pub fn page(link: &LinkResponse, qr_svg: Option<&[u8]>) -> Response {
+    let code = escape(&link.short_code);
+    let opens_later = link.active_from.filter(|from| *from > Utc::now());
+
+    let destination = if link.password_protected {
+        "<p>Destination: hidden, this link is password protected</p>".to_string()
+    } else if opens_later.is_some() {
+        "<p>Destination: hidden until this link opens</p>".to_string()
+    } else {
+        format!(
+            r#"<p>Destination: <strong class="destination">{}</strong></p>"#,
+            escape(&link.target_url)
+        )
+    };
+
+    let expiry = match link.expires_at {
+        Some(expires_at) => format!("<p>Expires: {}</p>", datetime(expires_at)),
+        None => "<p>Expires: never</p>".to_string(),
+    };
+
+    let active_from = opens_later
+        .map(|from| format!("<p>Opens: {}</p>", datetime(from)))
+        .unwrap_or_default();
+
+    let qr = qr_svg
+        .map(|svg| {
+            format!(
+                r#"<img src="data:image/svg+xml;base64,{}" alt="QR code for {}" width="200" height="200">"#,
+                base64::engine::general_purpose::STANDARD.encode(svg),
+                escape(&link.short_url)
+            )
+        })
+        .unwrap_or_default();
+
+    // The unlock form asks for the password; other links are continued by posting
+    // to the short link, which counts the redirect
+    let action = if link.password_protected {
+        format!(r#"<a class="btn-primary" href="/{code}">Continue</a>"#)
+    } else {
+        format!(
+            r#"<form method="post" action="/{code}"><button class="btn-primary" type="submit">Continue</button></form>"#
+        )
+    };
+
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>Link preview</title>
+<link rel="stylesheet" href="/styles.css">
+</head>
+<body>
+<header class="header">
+<div class="header-left"><span class="logo">QRLink</span></div>
+</header>
+<main class="main">
+<div class="card">
+<h1 class="label">{short_url}</h1>
+<div class="result">
+{qr}
+<div class="info">
+{destination}
+<p>Created: {created}</p>
+{expiry}
+{active_from}
+</div>
+{action}
+</div>
+</div>
+</main>
+</body>
+</html>
+"#,
+        short_url = escape(&link.short_url),
+        qr = qr,
+        destination = destination,
+        created = datetime(link.created_at),
+        expiry = expiry,
+        active_from = active_from,
+        action = action,
+    );
+
+    (
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "no-store")],
+        Html(html),
+    )
+        .into_response()
+}
+
+fn datetime(at: DateTime<Utc>) -> String {
+    format!(
+        r#"<time datetime="{}">{}</time>"#,
+        at.to_rfc3339(),
+        at.format("%B %-d, %Y at %H:%M UTC")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Link, ShortCode};
+    use url::Url;
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_page_hides_destination_of_protected_links() {
+        let mut link = Link::new(
+            ShortCode::from_existing("Ab3kP9x".to_string()),
+            Url::parse("https://example.com/?a=1&b=<2>").unwrap(),
+            Utc::now(),
+        );
+
+        let html = body(page(&LinkResponse::from_link(&link, "http://s.test"), None)).await;
+        assert!(html.contains("https://example.com/?a=1&amp;b=%3C2%3E"));
+        assert!(html.contains(r#"<form method="post" action="/Ab3kP9x">"#));
+
+        link.password_hash = Some("hash".to_string());
+        let html = body(page(&LinkResponse::from_link(&link, "http://s.test"), None)).await;
+        assert!(!html.contains("example.com"));
+        assert!(html.contains(r#"href="/Ab3kP9x""#));
+    }
+
+    #[tokio::test]
+    async fn test_page_hides_destination_until_link_opens() {
+        let mut link = Link::new(
+            ShortCode::from_existing("Ab3kP9x".to_string()),
+            Url::parse("https://example.com/launch").unwrap(),
+            Utc::now(),
+        );
+
+        link.active_from = Some(Utc::now() + chrono::Duration::days(1));
+        let html = body(page(&LinkResponse::from_link(&link, "http://s.test"), None)).await;
+        assert!(!html.contains("example.com/launch"));
+        assert!(html.contains("Opens:"));
+
+        link.active_from = Some(Utc::now() - chrono::Duration::days(1));
+        let html = body(page(&LinkResponse::from_link(&link, "http://s.test"), None)).await;
+        assert!(html.contains("https://example.com/launch"));
+    }
+}
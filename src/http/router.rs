@@ -1,16 +1,25 @@
+use std::time::Instant;
+
 use axum::{
     Router,
-    extract::connect_info::IntoMakeServiceWithConnectInfo,
-    routing::{delete, get, post},
+    extract::{MatchedPath, Request, connect_info::IntoMakeServiceWithConnectInfo},
+    middleware::{self, Next},
+    response::Response,
+    routing::{delete, get, patch, post, put},
 };
 use tower_http::{
     cors::{Any, CorsLayer},
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
+use uuid::Uuid;
 
+use crate::domain::RedirectType;
+use crate::metrics;
 use crate::rate_limit::RateLimiter;
-use crate::service::{LinkService, QrService};
+use crate::service::{
+    AnalyticsService, ApiKeyService, LinkService, QrService, RedirectRuleService,
+};
 
 use super::handlers;
 
@@ -19,35 +28,56 @@ use super::handlers;
 pub struct AppState {
     pub link_service: LinkService,
     pub qr_service: QrService,
+    pub analytics_service: AnalyticsService,
+    pub api_key_service: ApiKeyService,
+    pub redirect_rule_service: RedirectRuleService,
     pub rate_limiter: RateLimiter,
+    /// Failed password attempts per IP
+    pub unlock_ip_limiter: RateLimiter,
+    /// Failed password attempts per link
+    pub unlock_link_limiter: RateLimiter<Uuid>,
+    /// Admin secret; when set, link management requires it or a scoped API key
     pub admin_secret: Option<String>,
+    /// When true, link creation and QR generation require a scoped API key
+    pub require_api_key: bool,
+    /// Redirect status for links that do not set their own
+    pub default_redirect_type: RedirectType,
 }
 
 /// Create the main application router.
 pub fn create_router(
-    link_service: LinkService,
-    qr_service: QrService,
-    rate_limiter: RateLimiter,
-    admin_secret: Option<String>,
+    state: AppState,
 ) -> IntoMakeServiceWithConnectInfo<Router, std::net::SocketAddr> {
-    let state = AppState {
-        link_service,
-        qr_service,
-        rate_limiter,
-        admin_secret,
-    };
-
-    // API routes (public, no authentication)
+    // API routes (access rules are enforced per handler by the auth extractors)
     let api_routes = Router::new()
         .route("/links", post(handlers::create_link))
         .route("/links", get(handlers::list_links))
+        .route("/links/{id}", patch(handlers::update_link))
         .route("/links/{id}", delete(handlers::delete_link))
-        .route("/qr", post(handlers::create_qr));
+        .route("/links/{id}/stats", get(handlers::link_stats))
+        .route("/links/{id}/qr", get(handlers::link_qr))
+        .route("/links/{id}/rules", get(handlers::get_link_rules))
+        .route("/links/{id}/rules", put(handlers::set_link_rules))
+        .route("/links/{id}/variants", put(handlers::set_link_variants))
+        .route("/campaigns/{campaign}/stats", get(handlers::campaign_stats))
+        .route("/qr", post(handlers::create_qr))
+        .route("/keys", post(handlers::create_api_key))
+        .route("/keys", get(handlers::list_api_keys))
+        .route("/keys/{id}", delete(handlers::revoke_api_key))
+        .route_layer(middleware::from_fn(track_latency));
 
     // Public routes
     let public_routes = Router::new()
         .route("/health", get(handlers::health_check))
-        .route("/{short_code}", get(handlers::redirect));
+        .route("/metrics", get(handlers::metrics))
+        .route(
+            "/{short_code}",
+            get(handlers::redirect).post(handlers::unlock),
+        )
+        .route("/{short_code}/preview", get(handlers::preview))
+        .route("/{short_code}/qr", get(handlers::short_link_qr))
+        .route("/{short_code}/{*rest}", get(handlers::redirect_with_path))
+        .route_layer(middleware::from_fn(track_latency));
 
     // Serve static files from public directory
     let static_service = ServeDir::new("public");
@@ -70,3 +100,23 @@ pub fn create_router(
         .with_state(state)
         .into_make_service_with_connect_info::<std::net::SocketAddr>()
 }
+
+/// Record handler latency, labelled by route template rather than raw path
+/// so short codes do not create one series each.
+async fn track_latency(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+
+    let response = next.run(request).await;
+
+    metrics::HTTP_REQUEST_SECONDS
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .observe(started.elapsed().as_secs_f64());
+
+    response
+}
@@ -0,0 +1,72 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Response},
+};
+
+/// Render the password form for a protected link, with an optional error message.
+pub fn page(status: StatusCode, short_code: &str, message: Option<&str>) -> Response {
+    let message = message
+        .map(|m| format!(r#"<p class="error">{}</p>"#, escape(m)))
+        .unwrap_or_default();
+
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>Protected link</title>
+<style>
+body {{ font-family: system-ui, sans-serif; display: flex; justify-content: center; padding-top: 15vh; background: #f5f5f5; }}
+form {{ background: #fff; padding: 2rem; border-radius: 8px; box-shadow: 0 1px 4px rgba(0,0,0,.1); min-width: 18rem; }}
+input, button {{ width: 100%; box-sizing: border-box; padding: .6rem; margin-top: .8rem; font-size: 1rem; }}
+.error {{ color: #b00020; }}
+</style>
+</head>
+<body>
+<form method="post" action="/{code}">
+<h1>Protected link</h1>
+<p>Enter the password to continue.</p>
+{message}
+<input type="password" name="password" autocomplete="current-password" required autofocus>
+<button type="submit">Continue</button>
+</form>
+</body>
+</html>
+"#,
+        code = escape(short_code),
+        message = message,
+    );
+
+    (status, [(header::CACHE_CONTROL, "no-store")], Html(html)).into_response()
+}
+
+/// Escape text for inclusion in HTML content or attribute values.
+pub(super) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+}
@@ -1,18 +1,12 @@
-mod config;
-mod domain;
-mod error;
-mod http;
-mod qr;
-mod rate_limit;
-mod repository;
-mod service;
-
 use std::net::SocketAddr;
 use std::time::Duration;
 
-use config::Config;
-use repository::{LinkRepository, init_db};
-use service::{LinkService, QrService};
+use qrlink::config::Config;
+use qrlink::domain::TtlBounds;
+use qrlink::service::{
+    AnalyticsService, ApiKeyService, LinkService, QrService, RedirectRuleService,
+};
+use qrlink::{http, rate_limit, repository};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -35,25 +29,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Base URL: {}", config.base_url);
     tracing::info!("Database: {}", config.database_url);
 
-    // Initialize database
-    let pool = init_db(&config.database_url).await?;
+    // Initialize database (backend selected by the DATABASE_URL scheme)
+    let repos = repository::connect(&config.database_url).await?;
     tracing::info!("Database initialized");
 
-    // Create repositories
-    let link_repo = LinkRepository::new(pool.clone());
-
     // Create services
-    let link_service = LinkService::new(link_repo, config.base_url.clone());
+    let redirect_rule_service = RedirectRuleService::new(repos.links.clone(), repos.rules);
+    let link_service =
+        LinkService::new(repos.links, config.base_url.clone()).with_ttl_bounds(TtlBounds {
+            min: config.min_ttl,
+            max: config.max_ttl,
+        });
     let qr_service = QrService::new(&config)?;
+    let analytics_service = AnalyticsService::new(repos.clicks, config.ip_hash_salt.clone());
+    let api_key_service = ApiKeyService::new(repos.api_keys);
 
     // Create rate limiter (IP-based, no authentication needed)
     let rate_limiter = rate_limit::RateLimiter::new(config.rate_limit_per_minute);
 
-    // Optional admin secret
-    let admin_secret = config.admin_secret.clone();
-
     // Create router
-    let app = http::create_router(link_service.clone(), qr_service, rate_limiter, admin_secret);
+    let app = http::create_router(http::AppState {
+        link_service: link_service.clone(),
+        qr_service,
+        analytics_service: analytics_service.clone(),
+        api_key_service,
+        redirect_rule_service,
+        rate_limiter,
+        unlock_ip_limiter: rate_limit::RateLimiter::new(config.unlock_attempts_per_ip),
+        unlock_link_limiter: rate_limit::RateLimiter::new(config.unlock_attempts_per_link),
+        admin_secret: config.admin_secret.clone(),
+        require_api_key: config.require_api_key,
+        default_redirect_type: config.default_redirect_type,
+    });
 
     // Start cleanup task if enabled
     if config.cleanup_interval_minutes > 0 {
@@ -84,7 +91,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // Persist clicks still buffered in memory
+    analytics_service.flush().await;
+    tracing::info!("Shutdown complete");
 
     Ok(())
 }
+
+/// Resolve when the process receives Ctrl+C or SIGTERM (e.g., `docker stop`).
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown signal received");
+}
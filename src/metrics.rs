@@ -0,0 +1,178 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+/// Registry holding all service metrics.
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+/// Buckets for QR rendering, which ranges from sub-millisecond SVGs to large logo PNGs.
+const QR_RENDER_BUCKETS: &[f64] = &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(metric: T) -> T {
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("metric registered twice");
+    metric
+}
+
+/// Links created.
+pub static LINKS_CREATED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(
+        IntCounter::new("qrlink_links_created_total", "Number of links created")
+            .expect("valid metric"),
+    )
+});
+
+/// Redirect requests, by outcome (`ok`, `not_found`, `expired`, `exhausted`, `scheduled`).
+pub static REDIRECTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "qrlink_redirects_total",
+                "Number of redirect requests by outcome",
+            ),
+            &["outcome"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// QR codes generated, by format.
+pub static QR_GENERATED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "qrlink_qr_generated_total",
+                "Number of QR codes generated by format",
+            ),
+            &["format"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// QR rendering time in seconds, by format.
+pub static QR_RENDER_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "qrlink_qr_render_duration_seconds",
+                "Time spent rendering QR codes",
+            )
+            .buckets(QR_RENDER_BUCKETS.to_vec()),
+            &["format"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// QR renders served from the cache, by tier (`memory`, `disk`).
+pub static QR_CACHE_HITS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "qrlink_qr_cache_hits_total",
+                "Number of QR renders served from the cache by tier",
+            ),
+            &["tier"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// QR requests that had to be rendered.
+pub static QR_CACHE_MISSES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(
+        IntCounter::new(
+            "qrlink_qr_cache_misses_total",
+            "Number of QR requests not found in the cache",
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// QR requests rejected because the render pool was saturated.
+pub static QR_RENDER_REJECTIONS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(
+        IntCounter::new(
+            "qrlink_qr_render_rejections_total",
+            "Number of QR requests rejected because rendering was at capacity",
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Requests rejected by the rate limiter.
+pub static RATE_LIMIT_REJECTIONS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(
+        IntCounter::new(
+            "qrlink_rate_limit_rejections_total",
+            "Number of requests rejected by the rate limiter",
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Links deleted by the expiry cleanup.
+pub static CLEANUP_DELETED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(
+        IntCounter::new(
+            "qrlink_cleanup_deleted_links_total",
+            "Number of expired links deleted by cleanup",
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// HTTP handler latency in seconds, by method, route and status.
+pub static HTTP_REQUEST_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "qrlink_http_request_duration_seconds",
+                "HTTP request handling time",
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Render all metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    // Touch every metric so it is exported (as zero) before its first use
+    LazyLock::force(&LINKS_CREATED);
+    LazyLock::force(&REDIRECTS);
+    LazyLock::force(&QR_GENERATED);
+    LazyLock::force(&QR_RENDER_SECONDS);
+    LazyLock::force(&QR_CACHE_HITS);
+    LazyLock::force(&QR_CACHE_MISSES);
+    LazyLock::force(&QR_RENDER_REJECTIONS);
+    LazyLock::force(&RATE_LIMIT_REJECTIONS);
+    LazyLock::force(&CLEANUP_DELETED);
+    LazyLock::force(&HTTP_REQUEST_SECONDS);
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_exposes_counters() {
+        REDIRECTS.with_label_values(&["not_found"]).inc();
+        QR_GENERATED.with_label_values(&["svg"]).inc();
+
+        let text = render();
+        assert!(text.contains("# TYPE qrlink_links_created_total counter"));
+        assert!(text.contains("qrlink_redirects_total{outcome=\"not_found\"}"));
+        assert!(text.contains("qrlink_qr_generated_total{format=\"svg\"}"));
+    }
+}
@@ -1,4 +1,20 @@
+#[path = "qr/cache.rs"]
+mod cache;
+#[path = "qr/format.rs"]
+mod format;
 #[path = "qr/generator.rs"]
 mod generator;
+#[path = "qr/pool.rs"]
+mod pool;
+#[path = "qr/shapes.rs"]
+mod shapes;
+#[path = "qr/style.rs"]
+mod style;
 
+pub use cache::{QrCache, QrCacheConfig, QrCacheTier};
+pub use format::QrFormat;
 pub use generator::QrGenerator;
+pub use pool::{RenderPool, RenderPoolError};
+pub use style::{
+    QrColor, QrEcLevel, QrEyeShape, QrGradient, QrGradientKind, QrModuleShape, QrStyle,
+};
@@ -0,0 +1,424 @@
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::{QrFormat, QrStyle};
+
+/// Name of the on-disk directory for renders without a logo.
+const NO_LOGO_DIR: &str = "no-logo";
+
+/// Prefix of the on-disk directories for renders with a logo, followed by its fingerprint.
+const LOGO_DIR_PREFIX: &str = "logo-";
+
+/// Fraction of the disk limit kept when pruning, so pruning does not run on every write.
+const DISK_PRUNE_TARGET: f64 = 0.9;
+
+/// Limits of a [`QrCache`].
+#[derive(Debug, Clone, Default)]
+pub struct QrCacheConfig {
+    /// Maximum number of renders kept in memory (0 disables the memory tier)
+    pub max_entries: usize,
+    /// Maximum total size of the renders kept in memory, in bytes
+    pub max_bytes: usize,
+    /// Directory of the optional on-disk tier
+    pub dir: Option<PathBuf>,
+    /// Maximum total size of the on-disk tier, in bytes
+    pub disk_max_bytes: u64,
+}
+
+/// Where a cached render was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrCacheTier {
+    Memory,
+    Disk,
+}
+
+impl QrCacheTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QrCacheTier::Memory => "memory",
+            QrCacheTier::Disk => "disk",
+        }
+    }
+}
+
+/// Cache of rendered QR images, keyed by content, format, style and logo.
+///
+/// Renders are kept in a bounded in-memory LRU and, when a directory is
+/// configured, on disk so they survive restarts. On-disk renders are grouped by
+/// logo fingerprint; renders for any other logo are removed when the cache opens.
+pub struct QrCache {
+    memory: Mutex<MemoryTier>,
+    disk: Option<DiskTier>,
+    logo_fingerprint: Option<String>,
+}
+
+impl QrCache {
+    /// Open a cache for renders made with the given logo (if any).
+    pub fn new(config: &QrCacheConfig, logo_fingerprint: Option<&str>) -> Result<Self, String> {
+        let disk = config
+            .dir
+            .as_deref()
+            .map(|root| DiskTier::open(root, logo_fingerprint, config.disk_max_bytes))
+            .transpose()?;
+
+        Ok(Self {
+            memory: Mutex::new(MemoryTier::new(config.max_entries, config.max_bytes)),
+            disk,
+            logo_fingerprint: logo_fingerprint.map(str::to_string),
+        })
+    }
+
+    /// Cache key of a render.
+    pub fn key(&self, content: &str, format: QrFormat, style: &QrStyle) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.logo_fingerprint.as_deref().unwrap_or_default(),
+            content,
+            format.as_str(),
+            &style.foreground.to_string(),
+            &format!("{:?}", style.gradient),
+            &style.background.to_string(),
+            &style.size.to_string(),
+            &style.quiet_zone.to_string(),
+            &format!("{:?}", style.ec_level),
+            &style.logo.to_string(),
+            &format!("{:?}", style.module_shape),
+            &format!("{:?}", style.eye_outer_shape),
+            &format!("{:?}", style.eye_inner_shape),
+            &style.eye_outer_color().to_string(),
+            &style.eye_inner_color().to_string(),
+        ] {
+            // Length-prefixed so that field boundaries cannot be shifted
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Look up a render, promoting on-disk hits into memory.
+    pub fn get(&self, key: &str, format: QrFormat) -> Option<(Vec<u8>, QrCacheTier)> {
+        if let Some(data) = self.get_from_memory(key) {
+            return Some((data, QrCacheTier::Memory));
+        }
+
+        let data = self.disk.as_ref()?.get(key, format)?;
+        self.memory().insert(key, &data);
+        Some((data, QrCacheTier::Disk))
+    }
+
+    /// Look up a render in memory only, which is cheap enough for async contexts.
+    pub fn get_from_memory(&self, key: &str) -> Option<Vec<u8>> {
+        self.memory().get(key)
+    }
+
+    /// Store a render in every tier.
+    pub fn insert(&self, key: &str, format: QrFormat, data: &[u8]) {
+        self.memory().insert(key, data);
+        if let Some(disk) = &self.disk {
+            disk.insert(key, format, data);
+        }
+    }
+
+    fn memory(&self) -> std::sync::MutexGuard<'_, MemoryTier> {
+        // The tier holds no invariants a panicking holder could break mid-way
+        self.memory.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// In-memory LRU bounded by entry count and total size.
+struct MemoryTier {
+    max_entries: usize,
+    max_bytes: usize,
+    entries: HashMap<String, MemoryEntry>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, String>,
+    bytes: usize,
+    clock: u64,
+}
+
+struct MemoryEntry {
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+impl MemoryTier {
+    fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            max_entries,
+            max_bytes,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            bytes: 0,
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Vec<u8>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.last_used);
+        entry.last_used = self.clock;
+        self.recency.insert(self.clock, key.to_string());
+        Some(entry.data.clone())
+    }
+
+    fn insert(&mut self, key: &str, data: &[u8]) {
+        if self.max_entries == 0 || data.len() > self.max_bytes {
+            return;
+        }
+
+        self.remove(key);
+        self.clock += 1;
+        self.entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                data: data.to_vec(),
+                last_used: self.clock,
+            },
+        );
+        self.recency.insert(self.clock, key.to_string());
+        self.bytes += data.len();
+
+        while self.entries.len() > self.max_entries || self.bytes > self.max_bytes {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.bytes -= entry.data.len();
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+            self.bytes -= entry.data.len();
+        }
+    }
+}
+
+/// On-disk tier: one file per render, evicted by modification time.
+struct DiskTier {
+    dir: PathBuf,
+    max_bytes: u64,
+    bytes: Mutex<u64>,
+}
+
+impl DiskTier {
+    /// Open the directory for the given logo, removing renders made with other logos.
+    fn open(root: &Path, logo_fingerprint: Option<&str>, max_bytes: u64) -> Result<Self, String> {
+        let name = match logo_fingerprint {
+            Some(fingerprint) => format!("{}{}", LOGO_DIR_PREFIX, fingerprint),
+            None => NO_LOGO_DIR.to_string(),
+        };
+        let dir = root.join(&name);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create QR cache directory {:?}: {}", dir, e))?;
+
+        // Only directories this cache created are removed, in case the root is shared
+        let siblings =
+            fs::read_dir(root).map_err(|e| format!("Failed to read {:?}: {}", root, e))?;
+        for entry in siblings.flatten() {
+            let stale = entry.file_name().to_str().is_some_and(|other| {
+                other != name
+                    && other
+                        .strip_prefix(LOGO_DIR_PREFIX)
+                        .is_some_and(|f| f.chars().all(|c| c.is_ascii_hexdigit()))
+            });
+            if stale {
+                tracing::info!(
+                    "Removing QR renders for a previous logo: {:?}",
+                    entry.path()
+                );
+                if let Err(e) = fs::remove_dir_all(entry.path()) {
+                    tracing::warn!("Failed to remove {:?}: {}", entry.path(), e);
+                }
+            }
+        }
+
+        let bytes = cached_files(&dir).iter().map(|file| file.len).sum();
+        Ok(Self {
+            dir,
+            max_bytes,
+            bytes: Mutex::new(bytes),
+        })
+    }
+
+    fn path(&self, key: &str, format: QrFormat) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, format.as_str()))
+    }
+
+    fn get(&self, key: &str, format: QrFormat) -> Option<Vec<u8>> {
+        let path = self.path(key, format);
+        let data = fs::read(&path).ok()?;
+        // Mark as recently used so pruning keeps it
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(data)
+    }
+
+    fn insert(&self, key: &str, format: QrFormat, data: &[u8]) {
+        if data.len() as u64 > self.max_bytes {
+            return;
+        }
+
+        // Write then rename, so readers never see a partial file
+        let path = self.path(key, format);
+        let tmp = self
+            .dir
+            .join(format!("{}.{}.tmp", key, rand::random::<u32>()));
+        if let Err(e) = fs::write(&tmp, data).and_then(|_| fs::rename(&tmp, &path)) {
+            tracing::warn!("Failed to write QR render to {:?}: {}", path, e);
+            let _ = fs::remove_file(&tmp);
+            return;
+        }
+
+        let mut bytes = self.bytes.lock().unwrap_or_else(|e| e.into_inner());
+        *bytes += data.len() as u64;
+        if *bytes > self.max_bytes {
+            *bytes = self.prune();
+        }
+    }
+
+    /// Delete the least recently used files until under the target size.
+    /// Returns the remaining total size.
+    fn prune(&self) -> u64 {
+        let mut files = cached_files(&self.dir);
+        files.sort_by_key(|file| file.modified);
+
+        let target = (self.max_bytes as f64 * DISK_PRUNE_TARGET) as u64;
+        let mut total: u64 = files.iter().map(|file| file.len).sum();
+        for file in files {
+            if total <= target {
+                break;
+            }
+            match fs::remove_file(&file.path) {
+                Ok(()) => total -= file.len,
+                Err(e) => tracing::warn!("Failed to remove {:?}: {}", file.path, e),
+            }
+        }
+        total
+    }
+}
+
+struct CachedFile {
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+}
+
+/// Renders stored in a cache directory.
+fn cached_files(dir: &Path) -> Vec<CachedFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            let extension = path.extension().and_then(|e| e.to_str());
+            matches!(extension, Some("png") | Some("svg"))
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(CachedFile {
+                path: entry.path(),
+                len: metadata.len(),
+                modified: metadata.modified().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_only(max_entries: usize, max_bytes: usize) -> QrCache {
+        let config = QrCacheConfig {
+            max_entries,
+            max_bytes,
+            ..QrCacheConfig::default()
+        };
+        QrCache::new(&config, None).unwrap()
+    }
+
+    #[test]
+    fn test_key_covers_content_style_and_logo() {
+        let cache = memory_only(10, 1024);
+        let style = QrStyle::new(256);
+        let key = cache.key("https://example.com", QrFormat::Png, &style);
+
+        assert_eq!(key, cache.key("https://example.com", QrFormat::Png, &style));
+        assert_ne!(key, cache.key("https://example.org", QrFormat::Png, &style));
+        assert_ne!(key, cache.key("https://example.com", QrFormat::Svg, &style));
+        let larger = QrStyle::new(512);
+        assert_ne!(
+            key,
+            cache.key("https://example.com", QrFormat::Png, &larger)
+        );
+
+        let branded = QrCache::new(&QrCacheConfig::default(), Some("0123abcd")).unwrap();
+        assert_ne!(
+            key,
+            branded.key("https://example.com", QrFormat::Png, &style)
+        );
+    }
+
+    #[test]
+    fn test_memory_tier_evicts_least_recently_used() {
+        let cache = memory_only(2, 1024);
+        cache.insert("a", QrFormat::Png, b"aaaa");
+        cache.insert("b", QrFormat::Png, b"bbbb");
+        assert!(cache.get("a", QrFormat::Png).is_some());
+
+        // "b" is now the least recently used
+        cache.insert("c", QrFormat::Png, b"cccc");
+        assert!(cache.get("b", QrFormat::Png).is_none());
+        assert_eq!(
+            cache.get("a", QrFormat::Png),
+            Some((b"aaaa".to_vec(), QrCacheTier::Memory))
+        );
+
+        // The byte limit applies as well
+        let cache = memory_only(10, 6);
+        cache.insert("a", QrFormat::Png, b"aaaa");
+        cache.insert("b", QrFormat::Png, b"bbbb");
+        assert!(cache.get("a", QrFormat::Png).is_none());
+        assert!(cache.get("b", QrFormat::Png).is_some());
+    }
+
+    #[test]
+    fn test_disk_tier_survives_reopen_but_not_logo_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = QrCacheConfig {
+            max_entries: 10,
+            max_bytes: 1024,
+            dir: Some(dir.path().to_path_buf()),
+            disk_max_bytes: 1024,
+        };
+
+        let cache = QrCache::new(&config, Some("0123abcd")).unwrap();
+        cache.insert("a", QrFormat::Svg, b"<svg/>");
+
+        let reopened = QrCache::new(&config, Some("0123abcd")).unwrap();
+        assert_eq!(
+            reopened.get("a", QrFormat::Svg),
+            Some((b"<svg/>".to_vec(), QrCacheTier::Disk))
+        );
+        assert_eq!(
+            reopened.get("a", QrFormat::Svg).unwrap().1,
+            QrCacheTier::Memory
+        );
+
+        // A new logo drops renders made with the old one
+        let rebranded = QrCache::new(&config, Some("4567ef01")).unwrap();
+        assert!(rebranded.get("a", QrFormat::Svg).is_none());
+        assert!(!dir.path().join("logo-0123abcd").exists());
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// Output format of a rendered QR code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    /// Raster PNG image
+    #[default]
+    Png,
+    /// Scalable vector graphics, suited for print
+    Svg,
+}
+
+impl QrFormat {
+    /// Lowercase name, as used in requests.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QrFormat::Png => "png",
+            QrFormat::Svg => "svg",
+        }
+    }
+
+    /// MIME type of the rendered output.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            QrFormat::Png => "image/png",
+            QrFormat::Svg => "image/svg+xml",
+        }
+    }
+
+    /// Pick the preferred supported format from an `Accept` header value.
+    /// Returns None when the header names no supported image type.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        let mut candidates: Vec<(f32, QrFormat)> = accept
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let format = match parts.next()?.to_ascii_lowercase().as_str() {
+                    "image/png" => QrFormat::Png,
+                    "image/svg+xml" => QrFormat::Svg,
+                    _ => return None,
+                };
+                let quality = parts
+                    .find_map(|param| param.strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (quality > 0.0).then_some((quality, format))
+            })
+            .collect();
+
+        // Stable sort keeps header order for equal weights
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.first().map(|(_, format)| *format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_accept_exact() {
+        assert_eq!(QrFormat::from_accept("image/svg+xml"), Some(QrFormat::Svg));
+        assert_eq!(QrFormat::from_accept("image/png"), Some(QrFormat::Png));
+    }
+
+    #[test]
+    fn test_from_accept_respects_order_and_quality() {
+        assert_eq!(
+            QrFormat::from_accept("image/svg+xml, image/png"),
+            Some(QrFormat::Svg)
+        );
+        assert_eq!(
+            QrFormat::from_accept("image/svg+xml;q=0.5, image/png"),
+            Some(QrFormat::Png)
+        );
+        assert_eq!(
+            QrFormat::from_accept("text/html, image/svg+xml;q=0.9, */*;q=0.8"),
+            Some(QrFormat::Svg)
+        );
+    }
+
+    #[test]
+    fn test_from_accept_unsupported() {
+        assert_eq!(QrFormat::from_accept("*/*"), None);
+        assert_eq!(QrFormat::from_accept("application/json"), None);
+        assert_eq!(QrFormat::from_accept("image/png;q=0"), None);
+    }
+}
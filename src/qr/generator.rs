@@ -1,15 +1,55 @@
+use base64::Engine;
 use image::{ImageEncoder, Rgba, RgbaImage};
-use qrcode::{EcLevel, QrCode};
+use qrcode::QrCode;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
 use std::path::PathBuf;
 
+use super::shapes::Symbol;
+use super::{QrColor, QrEcLevel, QrEyeShape, QrFormat, QrStyle};
+
 /// Maximum logo size as a fraction of QR code size (20%)
 const LOGO_MAX_SCALE: f32 = 0.20;
 
+/// Smaller logo sizes tried, in order, when a verified code does not decode
+const LOGO_FALLBACK_SCALES: &[f32] = &[0.15, 0.10];
+
+/// Margin added around rendered codes before decoding them, in pixels, so codes
+/// without a quiet zone are checked as they would appear on a light page
+const VERIFY_MARGIN: u32 = 32;
+
+/// Size of the larger side of rasterized SVG logos, in pixels
+const LOGO_RASTER_SIZE: f32 = 200.0;
+
+/// White padding around the logo, in pixels of the rendered image
+const LOGO_PADDING: u32 = 4;
+
+/// Lowest error correction level that survives the logo occluding the symbol center
+const MIN_LOGO_EC_LEVEL: QrEcLevel = QrEcLevel::Q;
+
 /// QR code generator with optional branding logo.
 #[derive(Clone)]
 pub struct QrGenerator {
     size: u32,
-    logo: Option<RgbaImage>,
+    logo: Option<Logo>,
+    verify: bool,
+}
+
+/// A branding logo, kept in raster form and, for SVG sources, in vector form.
+#[derive(Clone)]
+struct Logo {
+    raster: RgbaImage,
+    vector: Option<SvgLogo>,
+    /// Digest of the source file, which identifies the logo in cached renders
+    fingerprint: String,
+}
+
+/// Normalized SVG markup of a vector logo, with its intrinsic size.
+#[derive(Clone)]
+struct SvgLogo {
+    markup: String,
+    width: f32,
+    height: f32,
 }
 
 impl QrGenerator {
@@ -17,83 +57,337 @@ impl QrGenerator {
     pub fn new(size: u32, logo_path: Option<PathBuf>) -> Result<Self, String> {
         let logo = match logo_path {
             Some(path) => {
-                let logo_image = load_logo(&path)?;
-                Some(logo_image)
+                let logo = load_logo(&path)?;
+                Some(logo)
             }
             None => None,
         };
 
-        Ok(Self { size, logo })
+        Ok(Self {
+            size,
+            logo,
+            verify: false,
+        })
+    }
+
+    /// Decode every rendered code and check that it reads back its content.
+    /// When the logo makes a code unreadable, smaller logos are tried before failing.
+    pub fn with_verification(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Default style for this generator (configured size, black on white).
+    pub fn default_style(&self) -> QrStyle {
+        QrStyle::new(self.size)
+    }
+
+    /// Fingerprint of the configured logo file, which changes with its contents.
+    pub fn logo_fingerprint(&self) -> Option<&str> {
+        self.logo.as_ref().map(|logo| logo.fingerprint.as_str())
     }
 
-    /// Generate a QR code PNG for the given content.
-    pub fn generate(&self, content: &str) -> Result<Vec<u8>, String> {
-        // Create QR code with high error correction (required for logo overlay)
-        let qr = QrCode::with_error_correction_level(content, EcLevel::H)
+    /// Check that a style is valid and compatible with the configured logo.
+    pub fn validate(&self, style: &QrStyle) -> Result<(), String> {
+        style.validate()?;
+
+        if self.overlays_logo(style) && style.ec_level < MIN_LOGO_EC_LEVEL {
+            return Err(format!(
+                "Error correction level {:?} is too low for the logo overlay (minimum {:?}); \
+                 raise it or disable the logo",
+                style.ec_level, MIN_LOGO_EC_LEVEL
+            ));
+        }
+
+        // The decoder locates finder patterns by the corners of their outer ring,
+        // so it cannot read codes with curved rings
+        if self.verify && style.eye_outer_shape != QrEyeShape::Square {
+            return Err(format!(
+                "Eye shape {:?} cannot be verified to scan; use square outer eyes",
+                style.eye_outer_shape
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Generate a QR code for the given content in the requested format and style.
+    pub fn generate(
+        &self,
+        content: &str,
+        format: QrFormat,
+        style: &QrStyle,
+    ) -> Result<Vec<u8>, String> {
+        self.validate(style)?;
+
+        let qr = QrCode::with_error_correction_level(content, style.ec_level.to_qrcode())
             .map_err(|e| format!("Failed to create QR code: {}", e))?;
 
-        // Render QR code to image
-        let qr_image = qr
-            .render::<Rgba<u8>>()
-            .quiet_zone(true)
-            .min_dimensions(self.size, self.size)
-            .max_dimensions(self.size, self.size)
-            .build();
+        let data = self.render(&qr, format, style, LOGO_MAX_SCALE)?;
+        if !self.verify || decode(format, &data)?.as_deref() == Some(content) {
+            return Ok(data);
+        }
+
+        if self.overlays_logo(style) {
+            for &logo_scale in LOGO_FALLBACK_SCALES {
+                let data = self.render(&qr, format, style, logo_scale)?;
+                if decode(format, &data)?.as_deref() == Some(content) {
+                    tracing::debug!(
+                        "Logo shrunk to {:.0}% for the QR code to decode",
+                        logo_scale * 100.0
+                    );
+                    return Ok(data);
+                }
+            }
+        }
 
-        let mut img: RgbaImage = qr_image;
+        Err("Rendered QR code does not decode to its content".to_string())
+    }
+
+    /// Render the QR code with the logo (if drawn) at the given fraction of the symbol.
+    fn render(
+        &self,
+        qr: &QrCode,
+        format: QrFormat,
+        style: &QrStyle,
+        logo_scale: f32,
+    ) -> Result<Vec<u8>, String> {
+        match format {
+            QrFormat::Png => self.render_png(qr, style, logo_scale),
+            QrFormat::Svg => self
+                .render_svg(qr, style, logo_scale)
+                .map(String::into_bytes),
+        }
+    }
+
+    /// Whether the logo is drawn for the given style.
+    fn overlays_logo(&self, style: &QrStyle) -> bool {
+        style.logo && self.logo.is_some()
+    }
+
+    /// Render the QR code to a PNG image.
+    fn render_png(&self, qr: &QrCode, style: &QrStyle, logo_scale: f32) -> Result<Vec<u8>, String> {
+        let mut img = render_bitmap(qr, style);
 
         // Overlay logo if available
-        if let Some(logo) = &self.logo {
-            img = overlay_logo(img, logo)?;
-        }
-
-        // Encode to PNG
-        let mut png_bytes: Vec<u8> = Vec::new();
-        let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
-        encoder
-            .write_image(
-                img.as_raw(),
-                img.width(),
-                img.height(),
-                image::ExtendedColorType::Rgba8,
-            )
-            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        if let Some(logo) = self.logo.as_ref().filter(|_| style.logo) {
+            img = overlay_logo(img, &logo.raster, style.background, logo_scale)?;
+        }
+
+        encode_png(&img)
+    }
+
+    /// Render the QR code to an SVG document.
+    /// Coordinates are expressed in modules; the document is sized to `size` pixels.
+    fn render_svg(&self, qr: &QrCode, style: &QrStyle, logo_scale: f32) -> Result<String, String> {
+        let total = qr.width() + style.quiet_zone as usize * 2;
+        let symbol = Symbol::new(qr, style);
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{size}" height="{size}" viewBox="0 0 {total} {total}">"#,
+            size = style.size,
+            total = total
+        );
+        let _ = write!(
+            svg,
+            r#"<rect width="{total}" height="{total}" fill="{background}"/>"#,
+            background = style.background,
+        );
+        svg.push_str(&symbol.to_svg(style.quiet_zone as f64));
+
+        if let Some(logo) = self.logo.as_ref().filter(|_| style.logo) {
+            svg.push_str(&svg_logo(logo, total as f32, style, logo_scale)?);
+        }
 
-        Ok(png_bytes)
+        svg.push_str("</svg>");
+        Ok(svg)
     }
 }
 
+/// Build the SVG fragment placing the logo in the center of the symbol.
+fn svg_logo(logo: &Logo, total: f32, style: &QrStyle, logo_scale: f32) -> Result<String, String> {
+    let (logo_width, logo_height) = match &logo.vector {
+        Some(vector) => (vector.width, vector.height),
+        None => (logo.raster.width() as f32, logo.raster.height() as f32),
+    };
+
+    let max_logo_size = total * logo_scale;
+    let scale = max_logo_size / logo_width.max(logo_height);
+    let width = logo_width * scale;
+    let height = logo_height * scale;
+    let x = (total - width) / 2.0;
+    let y = (total - height) / 2.0;
+    let padding = LOGO_PADDING as f32 * total / style.size as f32;
+
+    let mut fragment = String::new();
+    let _ = write!(
+        fragment,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+        x - padding,
+        y - padding,
+        width + padding * 2.0,
+        height + padding * 2.0,
+        style.background
+    );
+
+    match &logo.vector {
+        Some(vector) => {
+            let _ = write!(
+                fragment,
+                r#"<g transform="translate({} {}) scale({})">{}</g>"#,
+                x, y, scale, vector.markup
+            );
+        }
+        None => {
+            let png = encode_png(&logo.raster)?;
+            let _ = write!(
+                fragment,
+                r#"<image x="{}" y="{}" width="{}" height="{}" xlink:href="data:image/png;base64,{}"/>"#,
+                x,
+                y,
+                width,
+                height,
+                base64::engine::general_purpose::STANDARD.encode(png)
+            );
+        }
+    }
+
+    Ok(fragment)
+}
+
+/// Draw the styled QR symbol onto a square canvas of `style.size` pixels.
+/// Modules get a whole number of pixels each; leftover pixels widen the quiet zone.
+fn render_bitmap(qr: &QrCode, style: &QrStyle) -> RgbaImage {
+    let width = qr.width() as u32;
+    let total = width + style.quiet_zone * 2;
+    let unit = (style.size / total).max(1);
+    let canvas = (unit * total).max(style.size);
+    let offset = (canvas - unit * total) / 2 + style.quiet_zone * unit;
+
+    let mut img = RgbaImage::from_pixel(canvas, canvas, style.background.to_rgba());
+    Symbol::new(qr, style).draw(&mut img, offset, unit);
+    img
+}
+
+/// Encode an RGBA image as PNG.
+fn encode_png(img: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png_bytes: Vec<u8> = Vec::new();
+    let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
+    encoder
+        .write_image(
+            img.as_raw(),
+            img.width(),
+            img.height(),
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+
+    Ok(png_bytes)
+}
+
+/// Decode a rendered QR code, returning its content if it can be read.
+fn decode(format: QrFormat, data: &[u8]) -> Result<Option<String>, String> {
+    let img = match format {
+        QrFormat::Png => image::load_from_memory(data)
+            .map_err(|e| format!("Failed to read rendered PNG: {}", e))?
+            .to_rgba8(),
+        QrFormat::Svg => {
+            let tree = resvg::usvg::Tree::from_data(data, &resvg::usvg::Options::default())
+                .map_err(|e| format!("Failed to parse rendered SVG: {}", e))?;
+            rasterize_svg(&tree, tree.size().width().max(tree.size().height()))?
+        }
+    };
+
+    // Luma on a white margin; the style guarantees dark modules on a lighter background
+    let (width, height) = img.dimensions();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        (width + VERIFY_MARGIN * 2) as usize,
+        (height + VERIFY_MARGIN * 2) as usize,
+        |x, y| {
+            let (x, y) = (x as u32, y as u32);
+            if x < VERIFY_MARGIN
+                || y < VERIFY_MARGIN
+                || x >= width + VERIFY_MARGIN
+                || y >= height + VERIFY_MARGIN
+            {
+                return 255;
+            }
+            let Rgba([r, g, b, _]) = *img.get_pixel(x - VERIFY_MARGIN, y - VERIFY_MARGIN);
+            ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+        },
+    );
+
+    Ok(prepared
+        .detect_grids()
+        .iter()
+        .find_map(|grid| grid.decode().ok())
+        .map(|(_, content)| content))
+}
+
 /// Load and prepare a logo image from file (PNG or SVG).
-fn load_logo(path: &PathBuf) -> Result<RgbaImage, String> {
+fn load_logo(path: &PathBuf) -> Result<Logo, String> {
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
         .map(|e| e.to_lowercase());
 
-    match extension.as_deref() {
-        Some("svg") => load_svg_logo(path),
-        Some("png") | Some("jpg") | Some("jpeg") => load_raster_logo(path),
-        _ => Err(format!(
-            "Unsupported logo format: {:?}. Use PNG or SVG.",
-            path
-        )),
-    }
+    let (raster, vector) = match extension.as_deref() {
+        Some("svg") => {
+            let (raster, vector) = load_svg_logo(path)?;
+            (raster, Some(vector))
+        }
+        Some("png") | Some("jpg") | Some("jpeg") => (load_raster_logo(path)?, None),
+        _ => {
+            return Err(format!(
+                "Unsupported logo format: {:?}. Use PNG or SVG.",
+                path
+            ));
+        }
+    };
+
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read logo file: {}", e))?;
+
+    Ok(Logo {
+        raster,
+        vector,
+        fingerprint: hex::encode(&Sha256::digest(&data)[..8]),
+    })
 }
 
-/// Load an SVG logo and render it to a raster image.
-fn load_svg_logo(path: &PathBuf) -> Result<RgbaImage, String> {
+/// Load an SVG logo, keeping a rasterized copy and its normalized markup.
+fn load_svg_logo(path: &PathBuf) -> Result<(RgbaImage, SvgLogo), String> {
     let svg_data = std::fs::read(path).map_err(|e| format!("Failed to read SVG file: {}", e))?;
 
     let options = resvg::usvg::Options::default();
     let tree = resvg::usvg::Tree::from_data(&svg_data, &options)
         .map_err(|e| format!("Failed to parse SVG: {}", e))?;
 
+    // Re-serialize through usvg so the markup is self-contained and can be nested
+    let write_options = resvg::usvg::WriteOptions {
+        id_prefix: Some("logo-".to_string()),
+        indent: resvg::usvg::Indent::None,
+        attributes_indent: resvg::usvg::Indent::None,
+        ..Default::default()
+    };
+    let vector = SvgLogo {
+        markup: tree.to_string(&write_options),
+        width: tree.size().width(),
+        height: tree.size().height(),
+    };
+
+    // Render at a reasonable size for logo overlay
+    Ok((rasterize_svg(&tree, LOGO_RASTER_SIZE)?, vector))
+}
+
+/// Render a parsed SVG to a raster image whose larger side is `max_dimension` pixels.
+fn rasterize_svg(tree: &resvg::usvg::Tree, max_dimension: f32) -> Result<RgbaImage, String> {
     let size = tree.size();
     let width = size.width() as u32;
     let height = size.height() as u32;
 
-    // Render at a reasonable size for logo overlay
-    let scale = 200.0 / width.max(height) as f32;
+    let scale = max_dimension / width.max(height) as f32;
     let scaled_width = (width as f32 * scale) as u32;
     let scaled_height = (height as f32 * scale) as u32;
 
@@ -104,7 +398,7 @@ fn load_svg_logo(path: &PathBuf) -> Result<RgbaImage, String> {
     pixmap.fill(resvg::tiny_skia::Color::WHITE);
 
     let transform = resvg::tiny_skia::Transform::from_scale(scale, scale);
-    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    resvg::render(tree, transform, &mut pixmap.as_mut());
 
     // Convert to image::RgbaImage
     let img = RgbaImage::from_raw(scaled_width, scaled_height, pixmap.take())
@@ -120,9 +414,14 @@ fn load_raster_logo(path: &PathBuf) -> Result<RgbaImage, String> {
 }
 
 /// Overlay the logo in the center of the QR code.
-fn overlay_logo(mut qr: RgbaImage, logo: &RgbaImage) -> Result<RgbaImage, String> {
+fn overlay_logo(
+    mut qr: RgbaImage,
+    logo: &RgbaImage,
+    background: QrColor,
+    logo_scale: f32,
+) -> Result<RgbaImage, String> {
     let qr_size = qr.width().min(qr.height());
-    let max_logo_size = (qr_size as f32 * LOGO_MAX_SCALE) as u32;
+    let max_logo_size = (qr_size as f32 * logo_scale) as u32;
 
     // Scale logo to fit
     let logo_width = logo.width();
@@ -143,17 +442,17 @@ fn overlay_logo(mut qr: RgbaImage, logo: &RgbaImage) -> Result<RgbaImage, String
     let x_offset = (qr.width() - new_width) / 2;
     let y_offset = (qr.height() - new_height) / 2;
 
-    // Create white background padding around logo
-    let padding = 4;
+    // Create background padding around logo
+    let padding = LOGO_PADDING;
     let bg_width = new_width + padding * 2;
     let bg_height = new_height + padding * 2;
     let bg_x = x_offset.saturating_sub(padding);
     let bg_y = y_offset.saturating_sub(padding);
 
-    // Draw white background rectangle
+    // Draw background rectangle
     for y in bg_y..(bg_y + bg_height).min(qr.height()) {
         for x in bg_x..(bg_x + bg_width).min(qr.width()) {
-            qr.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+            qr.put_pixel(x, y, background.to_rgba());
         }
     }
 
@@ -183,11 +482,16 @@ fn overlay_logo(mut qr: RgbaImage, logo: &RgbaImage) -> Result<RgbaImage, String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::qr::{QrGradient, QrGradientKind, QrModuleShape};
 
     #[test]
     fn test_generate_qr_without_logo() {
         let generator = QrGenerator::new(256, None).unwrap();
-        let result = generator.generate("https://example.com");
+        let result = generator.generate(
+            "https://example.com",
+            QrFormat::Png,
+            &generator.default_style(),
+        );
         assert!(result.is_ok());
 
         let png_data = result.unwrap();
@@ -199,7 +503,309 @@ mod tests {
     #[test]
     fn test_generate_qr_with_content() {
         let generator = QrGenerator::new(512, None).unwrap();
-        let result = generator.generate("https://s.company.local/Ab3kP9x");
+        let result = generator.generate(
+            "https://s.company.local/Ab3kP9x",
+            QrFormat::Png,
+            &generator.default_style(),
+        );
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_generate_svg_without_logo() {
+        let generator = QrGenerator::new(256, None).unwrap();
+        let svg_data = generator
+            .generate(
+                "https://example.com",
+                QrFormat::Svg,
+                &generator.default_style(),
+            )
+            .unwrap();
+
+        let tree =
+            resvg::usvg::Tree::from_data(&svg_data, &resvg::usvg::Options::default()).unwrap();
+        assert_eq!(tree.size().width(), 256.0);
+        assert_eq!(tree.size().height(), 256.0);
+    }
+
+    #[test]
+    fn test_generate_svg_embeds_vector_logo() {
+        let logo_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/logo.svg");
+        let generator = QrGenerator::new(512, Some(logo_path)).unwrap();
+        let svg_data = generator
+            .generate(
+                "https://example.com",
+                QrFormat::Svg,
+                &generator.default_style(),
+            )
+            .unwrap();
+        let svg = String::from_utf8(svg_data).unwrap();
+
+        // The logo is nested as vector markup rather than an embedded bitmap
+        assert!(svg.contains("<g transform="));
+        assert!(!svg.contains("data:image/png"));
+        assert!(resvg::usvg::Tree::from_str(&svg, &resvg::usvg::Options::default()).is_ok());
+    }
+
+    #[test]
+    fn test_generate_svg_embeds_raster_logo() {
+        let logo_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/qr-logo.png");
+        let generator = QrGenerator::new(512, Some(logo_path)).unwrap();
+        let svg_data = generator
+            .generate(
+                "https://example.com",
+                QrFormat::Svg,
+                &generator.default_style(),
+            )
+            .unwrap();
+        let svg = String::from_utf8(svg_data).unwrap();
+
+        assert!(svg.contains("data:image/png;base64,"));
+    }
+
+    #[test]
+    fn test_generate_png_exact_size_with_custom_style() {
+        let generator = QrGenerator::new(512, None).unwrap();
+        let style = QrStyle {
+            foreground: QrColor::parse("#1a237e").unwrap(),
+            background: QrColor::parse("#fff8e1").unwrap(),
+            size: 300,
+            quiet_zone: 2,
+            ec_level: QrEcLevel::L,
+            logo: true,
+            ..generator.default_style()
+        };
+        let png_data = generator
+            .generate("https://example.com", QrFormat::Png, &style)
+            .unwrap();
+
+        let img = image::load_from_memory(&png_data).unwrap().to_rgba8();
+        assert_eq!(img.dimensions(), (300, 300));
+        // Corner pixel lies in the quiet zone
+        assert_eq!(*img.get_pixel(0, 0), Rgba([0xff, 0xf8, 0xe1, 255]));
+    }
+
+    #[test]
+    fn test_generate_svg_uses_style_colors() {
+        let generator = QrGenerator::new(512, None).unwrap();
+        let style = QrStyle {
+            foreground: QrColor::parse("#1a237e").unwrap(),
+            ..generator.default_style()
+        };
+        let svg_data = generator
+            .generate("https://example.com", QrFormat::Svg, &style)
+            .unwrap();
+        let svg = String::from_utf8(svg_data).unwrap();
+        assert!(svg.contains(r##"fill="#1a237e""##));
+    }
+
+    #[test]
+    fn test_logo_requires_high_ec_level() {
+        let logo_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/logo.svg");
+        let generator = QrGenerator::new(512, Some(logo_path)).unwrap();
+
+        let low = QrStyle {
+            ec_level: QrEcLevel::M,
+            ..generator.default_style()
+        };
+        assert!(generator.validate(&low).is_err());
+        assert!(
+            generator
+                .generate("https://example.com", QrFormat::Png, &low)
+                .is_err()
+        );
+
+        // Allowed once the logo is disabled
+        let no_logo = QrStyle { logo: false, ..low };
+        assert!(generator.validate(&no_logo).is_ok());
+    }
+
+    #[test]
+    fn test_every_style_combination_decodes() {
+        let assets = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let content = "https://s.company.local/Ab3kP9x";
+        let navy_on_cream = (
+            QrColor::parse("#1a237e").unwrap(),
+            QrColor::parse("#fff8e1").unwrap(),
+        );
+
+        for logo in [None, Some("logo.svg"), Some("qr-logo.png")] {
+            let generator = QrGenerator::new(512, logo.map(|name| assets.join(name)))
+                .unwrap()
+                .with_verification(true);
+
+            for format in [QrFormat::Png, QrFormat::Svg] {
+                for ec_level in [QrEcLevel::L, QrEcLevel::M, QrEcLevel::Q, QrEcLevel::H] {
+                    for (size, quiet_zone) in [(128, 0), (384, 16)] {
+                        for (foreground, background) in
+                            [(QrColor::BLACK, QrColor::WHITE), navy_on_cream]
+                        {
+                            let style = QrStyle {
+                                foreground,
+                                background,
+                                size,
+                                quiet_zone,
+                                ec_level,
+                                logo: true,
+                                ..QrStyle::new(size)
+                            };
+                            // Low error correction levels are rejected with a logo
+                            if generator.validate(&style).is_err() {
+                                continue;
+                            }
+
+                            let data = generator
+                                .generate(content, format, &style)
+                                .unwrap_or_else(|e| panic!("{:?} {:?}: {}", format, style, e));
+                            assert_eq!(
+                                decode(format, &data).unwrap().as_deref(),
+                                Some(content),
+                                "{:?} {:?} {:?}",
+                                logo,
+                                format,
+                                style
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_module_and_eye_shape_decodes() {
+        let logo_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/logo.svg");
+        let generator = QrGenerator::new(512, Some(logo_path))
+            .unwrap()
+            .with_verification(true);
+        let content = "https://s.company.local/Ab3kP9x";
+
+        for module_shape in [
+            QrModuleShape::Square,
+            QrModuleShape::Dot,
+            QrModuleShape::Rounded,
+        ] {
+            for eye_inner_shape in [QrEyeShape::Square, QrEyeShape::Rounded, QrEyeShape::Circle] {
+                let style = QrStyle {
+                    module_shape,
+                    eye_inner_shape,
+                    eye_outer_color: Some(QrColor::parse("#1a237e").unwrap()),
+                    eye_inner_color: Some(QrColor::parse("#d26323").unwrap()),
+                    ..QrStyle::new(256)
+                };
+
+                for format in [QrFormat::Png, QrFormat::Svg] {
+                    let data = generator
+                        .generate(content, format, &style)
+                        .unwrap_or_else(|e| panic!("{:?} {:?}: {}", format, style, e));
+                    assert_eq!(decode(format, &data).unwrap().as_deref(), Some(content));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_curved_outer_eyes_rejected_with_verification() {
+        let verifying = QrGenerator::new(512, None).unwrap().with_verification(true);
+        let generator = QrGenerator::new(512, None).unwrap();
+
+        for eye_outer_shape in [QrEyeShape::Rounded, QrEyeShape::Circle] {
+            let style = QrStyle {
+                eye_outer_shape,
+                ..QrStyle::new(256)
+            };
+            assert!(verifying.validate(&style).is_err());
+            assert!(
+                verifying
+                    .generate("https://example.com", QrFormat::Png, &style)
+                    .is_err()
+            );
+            assert!(
+                generator
+                    .generate("https://example.com", QrFormat::Png, &style)
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_svg_eye_colors() {
+        let generator = QrGenerator::new(512, None).unwrap();
+        let style = QrStyle {
+            module_shape: QrModuleShape::Dot,
+            eye_outer_shape: QrEyeShape::Circle,
+            eye_inner_color: Some(QrColor::parse("#d26323").unwrap()),
+            ..generator.default_style()
+        };
+        let svg_data = generator
+            .generate("https://example.com", QrFormat::Svg, &style)
+            .unwrap();
+        let svg = String::from_utf8(svg_data).unwrap();
+
+        assert!(svg.contains(r##"fill="#d26323""##));
+        assert!(svg.contains("a0.5 0.5 0 0 1"));
+        assert!(resvg::usvg::Tree::from_str(&svg, &resvg::usvg::Options::default()).is_ok());
+    }
+
+    #[test]
+    fn test_gradients_decode() {
+        let logo_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/logo.svg");
+        let generator = QrGenerator::new(512, Some(logo_path))
+            .unwrap()
+            .with_verification(true);
+        let colors = vec![
+            QrColor::parse("#1a237e").unwrap(),
+            QrColor::parse("#d26323").unwrap(),
+            QrColor::BLACK,
+        ];
+
+        for kind in [
+            QrGradientKind::Linear { angle: 0 },
+            QrGradientKind::Linear { angle: 135 },
+            QrGradientKind::Radial,
+        ] {
+            let style = QrStyle {
+                gradient: Some(QrGradient {
+                    kind,
+                    colors: colors.clone(),
+                }),
+                module_shape: QrModuleShape::Rounded,
+                ..QrStyle::new(384)
+            };
+
+            for format in [QrFormat::Png, QrFormat::Svg] {
+                if let Err(e) = generator.generate("https://example.com", format, &style) {
+                    panic!("{:?} {:?}: {}", format, kind, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_png_gradient_varies_across_symbol() {
+        let generator = QrGenerator::new(512, None).unwrap();
+        let content = "https://example.com";
+        let width = QrCode::with_error_correction_level(content, qrcode::EcLevel::H)
+            .unwrap()
+            .width() as u32;
+        let navy = QrColor::parse("#1a237e").unwrap();
+        // 10 pixels per module, with a 4 module quiet zone
+        let style = QrStyle {
+            gradient: Some(QrGradient {
+                kind: QrGradientKind::Linear { angle: 0 },
+                colors: vec![QrColor::BLACK, navy],
+            }),
+            size: (width + 8) * 10,
+            quiet_zone: 4,
+            ..generator.default_style()
+        };
+        let png_data = generator.generate(content, QrFormat::Png, &style).unwrap();
+        let img = image::load_from_memory(&png_data).unwrap().to_rgba8();
+
+        // Outer edges of the top-left and top-right finder patterns
+        let left = img.get_pixel(45, 45);
+        let right = img.get_pixel(40 + width * 10 - 5, 45);
+        assert!(left[2] < 0x10);
+        assert!(right[2] > 0x70);
+    }
 }
@@ -0,0 +1,125 @@
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use tokio::sync::oneshot;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Why a job did not run on the [`RenderPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPoolError {
+    /// Every worker is busy and the queue is full
+    Saturated,
+    /// The job panicked
+    Failed,
+}
+
+/// Dedicated threads for CPU-heavy QR rendering, so it does not block the async runtime.
+///
+/// At most `workers` jobs run at once and at most `queue_depth` more wait for a
+/// worker; further jobs are rejected right away rather than piling up.
+pub struct RenderPool {
+    sender: SyncSender<Job>,
+}
+
+impl RenderPool {
+    pub fn new(workers: usize, queue_depth: usize) -> Result<Self, String> {
+        let (sender, receiver) = mpsc::sync_channel::<Job>(queue_depth);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for i in 0..workers.max(1) {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("qr-render-{}", i))
+                .spawn(move || work(&receiver))
+                .map_err(|e| format!("Failed to start QR render worker: {}", e))?;
+        }
+
+        Ok(Self { sender })
+    }
+
+    /// Run a job on a worker thread and wait for its result.
+    pub async fn run<T, F>(&self, job: F) -> Result<T, RenderPoolError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (result_tx, result_rx) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let _ = result_tx.send(job());
+        });
+
+        match self.sender.try_send(job) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => return Err(RenderPoolError::Saturated),
+            // Workers only exit once the pool is dropped
+            Err(TrySendError::Disconnected(_)) => return Err(RenderPoolError::Failed),
+        }
+
+        // The sender is dropped without a value when the job panics
+        result_rx.await.map_err(|_| RenderPoolError::Failed)
+    }
+}
+
+/// Worker loop: run jobs until the pool is dropped.
+fn work(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = {
+            let receiver = receiver.lock().unwrap_or_else(|e| e.into_inner());
+            match receiver.recv() {
+                Ok(job) => job,
+                Err(_) => return,
+            }
+        };
+
+        // Keep the worker alive; the caller sees the panic as a failed job
+        if catch_unwind(AssertUnwindSafe(job)).is_err() {
+            tracing::error!("QR render job panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[tokio::test]
+    async fn test_run_returns_job_result() {
+        let pool = RenderPool::new(2, 4).unwrap();
+        let name = pool
+            .run(|| thread::current().name().map(str::to_string))
+            .await
+            .unwrap();
+        assert!(name.unwrap().starts_with("qr-render-"));
+
+        // A panicking job fails without taking the worker down
+        assert_eq!(
+            pool.run(|| panic!("boom")).await,
+            Err::<(), _>(RenderPoolError::Failed)
+        );
+        assert_eq!(pool.run(|| 2 + 2).await, Ok(4));
+    }
+
+    #[tokio::test]
+    async fn test_run_rejects_when_saturated() {
+        let pool = RenderPool::new(1, 1).unwrap();
+
+        // Occupy the only worker, then fill the queue
+        let (started_tx, started_rx) = channel();
+        let (release_tx, release_rx) = channel::<()>();
+        pool.sender
+            .try_send(Box::new(move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            }))
+            .unwrap();
+        started_rx.recv().unwrap();
+        pool.sender.try_send(Box::new(|| ())).unwrap();
+
+        assert_eq!(pool.run(|| ()).await, Err(RenderPoolError::Saturated));
+        release_tx.send(()).unwrap();
+    }
+}
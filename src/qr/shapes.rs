@@ -0,0 +1,498 @@
+use image::{Rgba, RgbaImage};
+use qrcode::{Color, QrCode};
+use std::fmt::Write;
+
+use super::{QrColor, QrEyeShape, QrGradient, QrGradientKind, QrModuleShape, QrStyle};
+
+/// Side of a finder pattern, in modules
+const EYE_SIZE: usize = 7;
+
+/// Id of the gradient definition referenced by SVG layers
+const SVG_GRADIENT_ID: &str = "qrlink-gradient";
+
+/// Corner radius of rounded data modules, in modules
+const ROUNDED_MODULE_RADIUS: f64 = 0.3;
+
+/// Corner radius of the outer edge of rounded eye rings, in modules
+const ROUNDED_EYE_OUTER_RADIUS: f64 = 2.0;
+
+/// Corner radius of rounded eye centers, in modules
+const ROUNDED_EYE_INNER_RADIUS: f64 = 0.75;
+
+/// Samples per pixel side when rasterizing curved edges
+const SUPERSAMPLING: u32 = 4;
+
+/// A rectangle with rounded corners; a square with a radius of half its side is a circle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RoundedRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    radius: f64,
+}
+
+impl RoundedRect {
+    fn square(x: f64, y: f64, size: f64, radius: f64) -> Self {
+        Self {
+            x,
+            y,
+            width: size,
+            height: size,
+            radius,
+        }
+    }
+
+    /// The same rectangle, `scale` times larger and shifted by `offset` on both axes.
+    fn transform(&self, scale: f64, offset: f64) -> Self {
+        Self {
+            x: self.x * scale + offset,
+            y: self.y * scale + offset,
+            width: self.width * scale,
+            height: self.height * scale,
+            radius: self.radius * scale,
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        if x < self.x || y < self.y || x > self.x + self.width || y > self.y + self.height {
+            return false;
+        }
+        let r = self.radius;
+        let dx = x - x.clamp(self.x + r, self.x + self.width - r);
+        let dy = y - y.clamp(self.y + r, self.y + self.height - r);
+        dx * dx + dy * dy <= r * r
+    }
+
+    /// Fraction of the unit pixel at (`px`, `py`) covered by the rectangle.
+    fn coverage(&self, px: f64, py: f64) -> f64 {
+        if self.radius == 0.0 {
+            let overlap_x = (px + 1.0).min(self.x + self.width) - px.max(self.x);
+            let overlap_y = (py + 1.0).min(self.y + self.height) - py.max(self.y);
+            return overlap_x.max(0.0) * overlap_y.max(0.0);
+        }
+
+        let step = 1.0 / SUPERSAMPLING as f64;
+        let mut inside = 0;
+        for sy in 0..SUPERSAMPLING {
+            for sx in 0..SUPERSAMPLING {
+                let x = px + (sx as f64 + 0.5) * step;
+                let y = py + (sy as f64 + 0.5) * step;
+                if self.contains(x, y) {
+                    inside += 1;
+                }
+            }
+        }
+        inside as f64 / (SUPERSAMPLING * SUPERSAMPLING) as f64
+    }
+
+    /// Append the outline as SVG path data, shifted by `offset` on both axes.
+    fn write_path(&self, path: &mut String, offset: f64) {
+        let (x, y, r) = (self.x + offset, self.y + offset, self.radius);
+        if r == 0.0 {
+            let _ = write!(
+                path,
+                "M{} {}h{}v{}h-{}z",
+                x, y, self.width, self.height, self.width
+            );
+            return;
+        }
+
+        let (h, v) = (self.width - 2.0 * r, self.height - 2.0 * r);
+        let _ = write!(
+            path,
+            "M{x} {y}h{h}a{r} {r} 0 0 1 {r} {r}v{v}a{r} {r} 0 0 1 -{r} {r}h-{h}a{r} {r} 0 0 1 -{r} -{r}v-{v}a{r} {r} 0 0 1 {r} -{r}z",
+            x = x + r,
+            y = y,
+            h = h,
+            v = v,
+            r = r
+        );
+    }
+}
+
+/// A filled area: a rounded rectangle, optionally with a hole inside it.
+#[derive(Debug, Clone, Copy)]
+struct Shape {
+    outer: RoundedRect,
+    hole: Option<RoundedRect>,
+}
+
+impl From<RoundedRect> for Shape {
+    fn from(outer: RoundedRect) -> Self {
+        Shape { outer, hole: None }
+    }
+}
+
+/// Paint of a layer.
+#[derive(Debug, Clone)]
+enum Fill {
+    Solid(QrColor),
+    Gradient(QrGradient),
+}
+
+/// Shapes drawn with the same paint.
+struct Layer {
+    fill: Fill,
+    shapes: Vec<Shape>,
+}
+
+impl Layer {
+    /// Whether every edge is straight, so the layer can be drawn without anti-aliasing.
+    fn is_crisp(&self) -> bool {
+        self.shapes
+            .iter()
+            .all(|shape| shape.outer.radius == 0.0 && shape.hole.is_none_or(|h| h.radius == 0.0))
+    }
+}
+
+/// The shapes making up a styled QR symbol, in module coordinates excluding the quiet zone.
+/// Data modules and the outer and inner parts of the finder patterns ("eyes")
+/// are separate layers, each with its own shape and paint.
+pub(crate) struct Symbol {
+    /// Side of the symbol, in modules
+    width: f64,
+    layers: Vec<Layer>,
+}
+
+impl Symbol {
+    pub(crate) fn new(qr: &QrCode, style: &QrStyle) -> Self {
+        let width = qr.width();
+        let colors = qr.to_colors();
+        let eyes = [(0, 0), (width - EYE_SIZE, 0), (0, width - EYE_SIZE)];
+        let in_eye = |x: usize, y: usize| {
+            eyes.iter().any(|&(ex, ey)| {
+                (ex..ex + EYE_SIZE).contains(&x) && (ey..ey + EYE_SIZE).contains(&y)
+            })
+        };
+        let is_data = |x: usize, y: usize| colors[y * width + x] == Color::Dark && !in_eye(x, y);
+
+        let mut modules = Vec::new();
+        for y in 0..width {
+            let mut x = 0;
+            while x < width {
+                if !is_data(x, y) {
+                    x += 1;
+                    continue;
+                }
+                match style.module_shape {
+                    // One rectangle per horizontal run, which keeps SVG output small
+                    QrModuleShape::Square => {
+                        let start = x;
+                        while x < width && is_data(x, y) {
+                            x += 1;
+                        }
+                        modules.push(Shape::from(RoundedRect {
+                            x: start as f64,
+                            y: y as f64,
+                            width: (x - start) as f64,
+                            height: 1.0,
+                            radius: 0.0,
+                        }));
+                        continue;
+                    }
+                    QrModuleShape::Dot => {
+                        modules.push(RoundedRect::square(x as f64, y as f64, 1.0, 0.5).into())
+                    }
+                    QrModuleShape::Rounded => modules.push(
+                        RoundedRect::square(x as f64, y as f64, 1.0, ROUNDED_MODULE_RADIUS).into(),
+                    ),
+                }
+                x += 1;
+            }
+        }
+
+        let (outer_radius, hole_radius) = match style.eye_outer_shape {
+            QrEyeShape::Square => (0.0, 0.0),
+            QrEyeShape::Rounded => (ROUNDED_EYE_OUTER_RADIUS, ROUNDED_EYE_OUTER_RADIUS - 1.0),
+            QrEyeShape::Circle => (3.5, 2.5),
+        };
+        let inner_radius = match style.eye_inner_shape {
+            QrEyeShape::Square => 0.0,
+            QrEyeShape::Rounded => ROUNDED_EYE_INNER_RADIUS,
+            QrEyeShape::Circle => 1.5,
+        };
+
+        let mut outer = Vec::new();
+        let mut inner = Vec::new();
+        for (ex, ey) in eyes {
+            let (x, y) = (ex as f64, ey as f64);
+            outer.push(Shape {
+                outer: RoundedRect::square(x, y, 7.0, outer_radius),
+                hole: Some(RoundedRect::square(x + 1.0, y + 1.0, 5.0, hole_radius)),
+            });
+            inner.push(RoundedRect::square(x + 2.0, y + 2.0, 3.0, inner_radius).into());
+        }
+
+        // Eyes without a color of their own follow the foreground, gradient included
+        let foreground = match &style.gradient {
+            Some(gradient) => Fill::Gradient(gradient.clone()),
+            None => Fill::Solid(style.foreground),
+        };
+        let eye_fill =
+            |color: Option<QrColor>| color.map_or_else(|| foreground.clone(), Fill::Solid);
+
+        Self {
+            width: width as f64,
+            layers: vec![
+                Layer {
+                    fill: foreground.clone(),
+                    shapes: modules,
+                },
+                Layer {
+                    fill: eye_fill(style.eye_outer_color),
+                    shapes: outer,
+                },
+                Layer {
+                    fill: eye_fill(style.eye_inner_color),
+                    shapes: inner,
+                },
+            ],
+        }
+    }
+
+    /// Position along a gradient, from 0 to 1, of a point in module coordinates.
+    fn gradient_position(&self, kind: QrGradientKind, x: f64, y: f64) -> f64 {
+        let center = self.width / 2.0;
+        let (dx, dy) = (x - center, y - center);
+        match kind {
+            QrGradientKind::Linear { angle } => {
+                let (sin, cos) = (angle as f64).to_radians().sin_cos();
+                let half_length = center * (cos.abs() + sin.abs());
+                (dx * cos + dy * sin) / (2.0 * half_length) + 0.5
+            }
+            QrGradientKind::Radial => dx.hypot(dy) / (center * std::f64::consts::SQRT_2),
+        }
+    }
+
+    /// SVG definition of a gradient spanning the symbol, shifted by `offset` modules.
+    fn svg_gradient(&self, gradient: &QrGradient, offset: f64) -> String {
+        let center = self.width / 2.0 + offset;
+        let mut svg = String::new();
+        match gradient.kind {
+            QrGradientKind::Linear { angle } => {
+                let (sin, cos) = (angle as f64).to_radians().sin_cos();
+                let half_length = self.width / 2.0 * (cos.abs() + sin.abs());
+                let _ = write!(
+                    svg,
+                    r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{:.3}" y1="{:.3}" x2="{:.3}" y2="{:.3}">"#,
+                    SVG_GRADIENT_ID,
+                    center - cos * half_length,
+                    center - sin * half_length,
+                    center + cos * half_length,
+                    center + sin * half_length
+                );
+            }
+            QrGradientKind::Radial => {
+                let _ = write!(
+                    svg,
+                    r#"<radialGradient id="{}" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{:.3}">"#,
+                    SVG_GRADIENT_ID,
+                    center,
+                    center,
+                    self.width / 2.0 * std::f64::consts::SQRT_2
+                );
+            }
+        }
+
+        let last = (gradient.colors.len() - 1).max(1) as f64;
+        for (i, color) in gradient.colors.iter().enumerate() {
+            let _ = write!(
+                svg,
+                r#"<stop offset="{:.3}" stop-color="{}"/>"#,
+                i as f64 / last,
+                color
+            );
+        }
+
+        svg.push_str(match gradient.kind {
+            QrGradientKind::Linear { .. } => "</linearGradient>",
+            QrGradientKind::Radial => "</radialGradient>",
+        });
+        svg
+    }
+
+    /// Draw the symbol onto an image, with modules of `unit` pixels starting `offset`
+    /// pixels from the top-left corner. Curved edges are anti-aliased.
+    pub(crate) fn draw(&self, img: &mut RgbaImage, offset: u32, unit: u32) {
+        let (canvas_width, canvas_height) = img.dimensions();
+
+        for layer in &self.layers {
+            for shape in &layer.shapes {
+                let outer = shape.outer.transform(unit as f64, offset as f64);
+                let hole = shape
+                    .hole
+                    .map(|hole| hole.transform(unit as f64, offset as f64));
+
+                let x_end = ((outer.x + outer.width).ceil() as u32).min(canvas_width);
+                let y_end = ((outer.y + outer.height).ceil() as u32).min(canvas_height);
+                for py in outer.y.floor() as u32..y_end {
+                    for px in outer.x.floor() as u32..x_end {
+                        let (fx, fy) = (px as f64, py as f64);
+                        let coverage =
+                            outer.coverage(fx, fy) - hole.map_or(0.0, |h| h.coverage(fx, fy));
+                        if coverage <= 0.0 {
+                            continue;
+                        }
+
+                        let color = match &layer.fill {
+                            Fill::Solid(color) => *color,
+                            Fill::Gradient(gradient) => {
+                                let x = (fx + 0.5 - offset as f64) / unit as f64;
+                                let y = (fy + 0.5 - offset as f64) / unit as f64;
+                                gradient.color_at(self.gradient_position(gradient.kind, x, y))
+                            }
+                        };
+                        let Rgba([r, g, b, _]) = color.to_rgba();
+
+                        let bg = img.get_pixel(px, py);
+                        let blend = |bg: u8, fg: u8| {
+                            (bg as f64 + (fg as f64 - bg as f64) * coverage).round() as u8
+                        };
+                        let pixel = Rgba([blend(bg[0], r), blend(bg[1], g), blend(bg[2], b), 255]);
+                        img.put_pixel(px, py, pixel);
+                    }
+                }
+            }
+        }
+    }
+
+    /// SVG `<path>` elements drawing the symbol, shifted by `offset` modules for the quiet zone.
+    pub(crate) fn to_svg(&self, offset: f64) -> String {
+        let mut svg = String::new();
+
+        // Every gradient layer shares the style's gradient
+        if let Some(Fill::Gradient(gradient)) = self
+            .layers
+            .iter()
+            .map(|layer| &layer.fill)
+            .find(|fill| matches!(fill, Fill::Gradient(_)))
+        {
+            let _ = write!(svg, "<defs>{}</defs>", self.svg_gradient(gradient, offset));
+        }
+
+        for layer in &self.layers {
+            let mut path = String::new();
+            for shape in &layer.shapes {
+                shape.outer.write_path(&mut path, offset);
+                if let Some(hole) = shape.hole {
+                    hole.write_path(&mut path, offset);
+                }
+            }
+
+            match &layer.fill {
+                Fill::Solid(color) => {
+                    let _ = write!(svg, r#"<path fill="{}" fill-rule="evenodd""#, color);
+                }
+                Fill::Gradient(_) => {
+                    let _ = write!(
+                        svg,
+                        r#"<path fill="url(#{})" fill-rule="evenodd""#,
+                        SVG_GRADIENT_ID
+                    );
+                }
+            }
+            if layer.is_crisp() {
+                svg.push_str(r#" shape-rendering="crispEdges""#);
+            }
+            let _ = write!(svg, r#" d="{}"/>"#, path);
+        }
+
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circle_coverage() {
+        let circle = RoundedRect::square(0.0, 0.0, 4.0, 2.0);
+        assert!(circle.contains(2.0, 2.0));
+        assert!(!circle.contains(0.1, 0.1));
+        assert_eq!(circle.coverage(1.0, 1.0), 1.0);
+        let corner = circle.coverage(0.0, 0.0);
+        assert!(corner > 0.0 && corner < 1.0);
+        assert_eq!(circle.coverage(10.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_square_coverage_is_exact() {
+        let square = RoundedRect::square(1.5, 1.0, 2.0, 0.0);
+        assert_eq!(square.coverage(1.0, 1.0), 0.5);
+        assert_eq!(square.coverage(2.0, 2.0), 1.0);
+        assert_eq!(square.coverage(4.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_square_modules_merge_into_runs() {
+        let qr = QrCode::new("https://example.com").unwrap();
+        let style = QrStyle::new(256);
+        let symbol = Symbol::new(&qr, &style);
+
+        let dark_data = qr.to_colors().iter().filter(|c| **c == Color::Dark).count() - 3 * (24 + 9);
+        let modules = &symbol.layers[0].shapes;
+        assert!(modules.len() < dark_data);
+        assert_eq!(
+            modules.iter().map(|s| s.outer.width).sum::<f64>(),
+            dark_data as f64
+        );
+        assert!(symbol.layers.iter().all(Layer::is_crisp));
+    }
+
+    #[test]
+    fn test_eye_shapes_and_colors() {
+        let qr = QrCode::new("https://example.com").unwrap();
+        let orange = QrColor::parse("#d26323").unwrap();
+        let style = QrStyle {
+            module_shape: QrModuleShape::Dot,
+            eye_outer_shape: QrEyeShape::Rounded,
+            eye_inner_shape: QrEyeShape::Circle,
+            eye_inner_color: Some(orange),
+            ..QrStyle::new(256)
+        };
+        let symbol = Symbol::new(&qr, &style);
+
+        assert_eq!(symbol.layers[1].shapes.len(), 3);
+        assert!(matches!(symbol.layers[2].fill, Fill::Solid(color) if color == orange));
+        assert_eq!(symbol.layers[2].shapes[0].outer.radius, 1.5);
+
+        let svg = symbol.to_svg(4.0);
+        assert!(svg.contains(r##"fill="#d26323""##));
+        assert!(!svg.contains("crispEdges"));
+    }
+
+    #[test]
+    fn test_gradient_spans_symbol() {
+        let qr = QrCode::new("https://example.com").unwrap();
+        let symbol = Symbol::new(&qr, &QrStyle::new(256));
+        let width = symbol.width;
+        let linear = |angle| QrGradientKind::Linear { angle };
+
+        assert!(symbol.gradient_position(linear(0), 0.0, 3.0).abs() < 1e-9);
+        assert!((symbol.gradient_position(linear(0), width, 3.0) - 1.0).abs() < 1e-9);
+        assert!((symbol.gradient_position(linear(90), 3.0, width) - 1.0).abs() < 1e-9);
+        assert!((symbol.gradient_position(linear(45), width, width) - 1.0).abs() < 1e-9);
+        assert!(symbol.gradient_position(QrGradientKind::Radial, width / 2.0, width / 2.0) < 1e-9);
+        assert!((symbol.gradient_position(QrGradientKind::Radial, 0.0, 0.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gradient_fills_eyes_without_color() {
+        let qr = QrCode::new("https://example.com").unwrap();
+        let style = QrStyle {
+            gradient: Some(QrGradient {
+                kind: QrGradientKind::Radial,
+                colors: vec![QrColor::BLACK, QrColor::parse("#1a237e").unwrap()],
+            }),
+            eye_inner_color: Some(QrColor::parse("#d26323").unwrap()),
+            ..QrStyle::new(256)
+        };
+
+        let svg = Symbol::new(&qr, &style).to_svg(4.0);
+        assert_eq!(svg.matches("<radialGradient").count(), 1);
+        assert_eq!(svg.matches(r#"fill="url(#qrlink-gradient)""#).count(), 2);
+        assert!(svg.contains(r##"fill="#d26323""##));
+    }
+}
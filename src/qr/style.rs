@@ -0,0 +1,481 @@
+use serde::{Deserialize, Serialize};
+
+/// Smallest accepted image size in pixels.
+pub const MIN_SIZE: u32 = 128;
+
+/// Largest accepted image size in pixels.
+pub const MAX_SIZE: u32 = 2048;
+
+/// Largest accepted quiet zone, in modules.
+pub const MAX_QUIET_ZONE: u32 = 16;
+
+/// Quiet zone recommended by the QR specification, in modules.
+const DEFAULT_QUIET_ZONE: u32 = 4;
+
+/// Most colors accepted in a gradient.
+pub const MAX_GRADIENT_COLORS: usize = 8;
+
+/// Minimum WCAG contrast ratio between foreground and background.
+/// Below this, phone cameras struggle to separate modules from the background.
+const MIN_CONTRAST_RATIO: f64 = 3.0;
+
+/// Rendering options for a QR code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrStyle {
+    /// Color of dark modules
+    pub foreground: QrColor,
+    /// Gradient filling dark modules in place of the foreground color
+    pub gradient: Option<QrGradient>,
+    /// Color of light modules and the quiet zone
+    pub background: QrColor,
+    /// Image width and height in pixels
+    pub size: u32,
+    /// Quiet zone width in modules
+    pub quiet_zone: u32,
+    /// Error correction level
+    pub ec_level: QrEcLevel,
+    /// Whether to overlay the branding logo (when one is configured)
+    pub logo: bool,
+    /// Shape of data modules
+    pub module_shape: QrModuleShape,
+    /// Shape of the outer ring of the finder patterns ("eyes")
+    pub eye_outer_shape: QrEyeShape,
+    /// Shape of the center of the finder patterns
+    pub eye_inner_shape: QrEyeShape,
+    /// Color of the outer ring of the finder patterns (defaults to the foreground)
+    pub eye_outer_color: Option<QrColor>,
+    /// Color of the center of the finder patterns (defaults to the foreground)
+    pub eye_inner_color: Option<QrColor>,
+}
+
+impl QrStyle {
+    /// Default style: black on white, spec quiet zone, highest error correction.
+    pub fn new(size: u32) -> Self {
+        QrStyle {
+            foreground: QrColor::BLACK,
+            gradient: None,
+            background: QrColor::WHITE,
+            size,
+            quiet_zone: DEFAULT_QUIET_ZONE,
+            ec_level: QrEcLevel::H,
+            logo: true,
+            module_shape: QrModuleShape::Square,
+            eye_outer_shape: QrEyeShape::Square,
+            eye_inner_shape: QrEyeShape::Square,
+            eye_outer_color: None,
+            eye_inner_color: None,
+        }
+    }
+
+    /// Color of the outer ring of the finder patterns.
+    pub fn eye_outer_color(&self) -> QrColor {
+        self.eye_outer_color.unwrap_or(self.foreground)
+    }
+
+    /// Color of the center of the finder patterns.
+    pub fn eye_inner_color(&self) -> QrColor {
+        self.eye_inner_color.unwrap_or(self.foreground)
+    }
+
+    /// Check that the style produces a scannable code.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&self.size) {
+            return Err(format!(
+                "Size must be between {} and {} pixels",
+                MIN_SIZE, MAX_SIZE
+            ));
+        }
+
+        if self.quiet_zone > MAX_QUIET_ZONE {
+            return Err(format!(
+                "Quiet zone must be at most {} modules",
+                MAX_QUIET_ZONE
+            ));
+        }
+
+        self.check_contrast("Foreground", self.foreground)?;
+        if let Some(gradient) = &self.gradient {
+            gradient.validate()?;
+            // Every stop is at least as dark as the lightest one
+            self.check_contrast("Gradient", gradient.lightest())?;
+        }
+        if let Some(color) = self.eye_outer_color {
+            self.check_contrast("Outer eye", color)?;
+        }
+        if let Some(color) = self.eye_inner_color {
+            self.check_contrast("Inner eye", color)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that a dark color stands out enough from the background.
+    fn check_contrast(&self, name: &str, color: QrColor) -> Result<(), String> {
+        if color.luminance() >= self.background.luminance() {
+            return Err(format!(
+                "{} color must be darker than background color",
+                name
+            ));
+        }
+
+        let ratio = color.contrast_ratio(&self.background);
+        if ratio < MIN_CONTRAST_RATIO {
+            return Err(format!(
+                "Contrast between {} and {} is too low to scan reliably ({:.2}:1, minimum {}:1)",
+                color, self.background, ratio, MIN_CONTRAST_RATIO
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A gradient between two or more colors, spread evenly over the symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QrGradient {
+    #[serde(flatten)]
+    pub kind: QrGradientKind,
+    /// Colors from the start to the end of the gradient
+    pub colors: Vec<QrColor>,
+}
+
+/// Direction of a gradient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum QrGradientKind {
+    /// Along a line through the symbol center, at `angle` degrees clockwise from
+    /// left-to-right (90 runs top to bottom)
+    Linear {
+        #[serde(default)]
+        angle: u16,
+    },
+    /// Outwards from the symbol center to its corners
+    Radial,
+}
+
+impl QrGradient {
+    fn validate(&self) -> Result<(), String> {
+        if !(2..=MAX_GRADIENT_COLORS).contains(&self.colors.len()) {
+            return Err(format!(
+                "Gradient must have between 2 and {} colors",
+                MAX_GRADIENT_COLORS
+            ));
+        }
+
+        if let QrGradientKind::Linear { angle } = self.kind
+            && angle >= 360
+        {
+            return Err("Gradient angle must be between 0 and 359 degrees".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// The color with the highest luminance, which has the lowest contrast on the background.
+    pub fn lightest(&self) -> QrColor {
+        self.colors
+            .iter()
+            .copied()
+            .max_by(|a, b| a.luminance().total_cmp(&b.luminance()))
+            .unwrap_or(QrColor::BLACK)
+    }
+
+    /// Color at `t`, from 0 (first color) to 1 (last color), interpolated in sRGB.
+    pub(crate) fn color_at(&self, t: f64) -> QrColor {
+        let Some(segments) = self.colors.len().checked_sub(1).filter(|n| *n > 0) else {
+            return self.colors.first().copied().unwrap_or(QrColor::BLACK);
+        };
+
+        let position = t.clamp(0.0, 1.0) * segments as f64;
+        let index = (position.floor() as usize).min(segments - 1);
+        let local = position - index as f64;
+        let (QrColor(from), QrColor(to)) = (self.colors[index], self.colors[index + 1]);
+
+        let mut rgb = [0u8; 3];
+        for (i, channel) in rgb.iter_mut().enumerate() {
+            *channel = (from[i] as f64 + (to[i] as f64 - from[i] as f64) * local).round() as u8;
+        }
+        QrColor(rgb)
+    }
+}
+
+/// Shape of the data modules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrModuleShape {
+    #[default]
+    Square,
+    /// Circles filling the module
+    Dot,
+    /// Squares with rounded corners
+    Rounded,
+}
+
+/// Shape of a part of the finder patterns in the symbol corners.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrEyeShape {
+    #[default]
+    Square,
+    /// Square with rounded corners
+    Rounded,
+    Circle,
+}
+
+/// Error correction level, from lowest (L, ~7% recovery) to highest (H, ~30%).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum QrEcLevel {
+    L,
+    M,
+    Q,
+    H,
+}
+
+impl QrEcLevel {
+    pub(crate) fn to_qrcode(self) -> qrcode::EcLevel {
+        match self {
+            QrEcLevel::L => qrcode::EcLevel::L,
+            QrEcLevel::M => qrcode::EcLevel::M,
+            QrEcLevel::Q => qrcode::EcLevel::Q,
+            QrEcLevel::H => qrcode::EcLevel::H,
+        }
+    }
+}
+
+/// An opaque RGB color, written as `#rrggbb` in requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct QrColor(pub [u8; 3]);
+
+impl QrColor {
+    pub const BLACK: QrColor = QrColor([0, 0, 0]);
+    pub const WHITE: QrColor = QrColor([255, 255, 255]);
+
+    /// Parse a `#rrggbb` or `#rgb` hex color.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let hex = value
+            .strip_prefix('#')
+            .ok_or_else(|| format!("Invalid color '{}': expected #rrggbb", value))?;
+
+        let expanded: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return Err(format!("Invalid color '{}': expected #rrggbb", value)),
+        };
+
+        let mut rgb = [0u8; 3];
+        for (i, channel) in rgb.iter_mut().enumerate() {
+            *channel = u8::from_str_radix(&expanded[i * 2..i * 2 + 2], 16)
+                .map_err(|_| format!("Invalid color '{}': expected #rrggbb", value))?;
+        }
+
+        Ok(QrColor(rgb))
+    }
+
+    /// Relative luminance as defined by WCAG 2.x.
+    pub fn luminance(&self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let [r, g, b] = self.0;
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    /// WCAG contrast ratio between two colors, from 1.0 to 21.0.
+    pub fn contrast_ratio(&self, other: &QrColor) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    pub(crate) fn to_rgba(self) -> image::Rgba<u8> {
+        let [r, g, b] = self.0;
+        image::Rgba([r, g, b, 255])
+    }
+}
+
+impl std::fmt::Display for QrColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [r, g, b] = self.0;
+        write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+impl TryFrom<String> for QrColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        QrColor::parse(&value)
+    }
+}
+
+impl From<QrColor> for String {
+    fn from(color: QrColor) -> Self {
+        color.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(
+            QrColor::parse("#d26323").unwrap(),
+            QrColor([0xd2, 0x63, 0x23])
+        );
+        assert_eq!(QrColor::parse("#FFF").unwrap(), QrColor::WHITE);
+        assert!(QrColor::parse("d26323").is_err());
+        assert!(QrColor::parse("#d2632").is_err());
+        assert!(QrColor::parse("#zzzzzz").is_err());
+    }
+
+    #[test]
+    fn test_color_display_roundtrip() {
+        let color = QrColor::parse("#0A1b2C").unwrap();
+        assert_eq!(color.to_string(), "#0a1b2c");
+    }
+
+    #[test]
+    fn test_contrast_ratio_extremes() {
+        let ratio = QrColor::BLACK.contrast_ratio(&QrColor::WHITE);
+        assert!((ratio - 21.0).abs() < 0.01);
+        assert!((QrColor::WHITE.contrast_ratio(&QrColor::WHITE) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_default_style_is_valid() {
+        assert!(QrStyle::new(512).validate().is_ok());
+    }
+
+    #[test]
+    fn test_brand_color_is_valid() {
+        let style = QrStyle {
+            foreground: QrColor::parse("#d26323").unwrap(),
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_ok());
+    }
+
+    #[test]
+    fn test_low_contrast_rejected() {
+        let style = QrStyle {
+            foreground: QrColor::parse("#cccccc").unwrap(),
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_err());
+    }
+
+    #[test]
+    fn test_inverted_colors_rejected() {
+        let style = QrStyle {
+            foreground: QrColor::WHITE,
+            background: QrColor::BLACK,
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_err());
+    }
+
+    #[test]
+    fn test_low_contrast_eye_color_rejected() {
+        let style = QrStyle {
+            eye_outer_color: Some(QrColor::parse("#d26323").unwrap()),
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_ok());
+        assert_eq!(style.eye_outer_color(), QrColor::parse("#d26323").unwrap());
+        assert_eq!(style.eye_inner_color(), QrColor::BLACK);
+
+        let style = QrStyle {
+            eye_inner_color: Some(QrColor::parse("#eeeeee").unwrap()),
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_err());
+    }
+
+    #[test]
+    fn test_gradient_checks_lightest_color() {
+        let navy = QrColor::parse("#1a237e").unwrap();
+        let gradient = |colors: Vec<QrColor>| QrStyle {
+            gradient: Some(QrGradient {
+                kind: QrGradientKind::Radial,
+                colors,
+            }),
+            ..QrStyle::new(512)
+        };
+
+        let style = gradient(vec![navy, QrColor::parse("#d26323").unwrap()]);
+        assert!(style.validate().is_ok());
+
+        let style = gradient(vec![navy, QrColor::parse("#cccccc").unwrap()]);
+        assert!(style.validate().unwrap_err().contains("#cccccc"));
+
+        assert!(gradient(vec![navy]).validate().is_err());
+    }
+
+    #[test]
+    fn test_gradient_angle_bounds() {
+        let style = QrStyle {
+            gradient: Some(QrGradient {
+                kind: QrGradientKind::Linear { angle: 360 },
+                colors: vec![QrColor::BLACK, QrColor::parse("#1a237e").unwrap()],
+            }),
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_err());
+    }
+
+    #[test]
+    fn test_gradient_color_at() {
+        let gradient = QrGradient {
+            kind: QrGradientKind::Linear { angle: 0 },
+            colors: vec![
+                QrColor::BLACK,
+                QrColor::parse("#804020").unwrap(),
+                QrColor::parse("#ff0000").unwrap(),
+            ],
+        };
+        assert_eq!(gradient.color_at(0.0), QrColor::BLACK);
+        assert_eq!(gradient.color_at(0.25), QrColor([0x40, 0x20, 0x10]));
+        assert_eq!(gradient.color_at(0.5), QrColor([0x80, 0x40, 0x20]));
+        assert_eq!(gradient.color_at(1.5), QrColor([0xff, 0, 0]));
+    }
+
+    #[test]
+    fn test_gradient_from_json() {
+        let gradient: QrGradient = serde_json::from_str(
+            r##"{"type": "linear", "angle": 45, "colors": ["#1a237e", "#d26323"]}"##,
+        )
+        .unwrap();
+        assert_eq!(gradient.kind, QrGradientKind::Linear { angle: 45 });
+        assert_eq!(gradient.colors.len(), 2);
+
+        let gradient: QrGradient =
+            serde_json::from_str(r##"{"type": "radial", "colors": ["#000", "#1a237e"]}"##).unwrap();
+        assert_eq!(gradient.kind, QrGradientKind::Radial);
+    }
+
+    #[test]
+    fn test_size_and_quiet_zone_bounds() {
+        assert!(QrStyle::new(MIN_SIZE - 1).validate().is_err());
+        assert!(QrStyle::new(MAX_SIZE + 1).validate().is_err());
+
+        let style = QrStyle {
+            quiet_zone: MAX_QUIET_ZONE + 1,
+            ..QrStyle::new(512)
+        };
+        assert!(style.validate().is_err());
+    }
+
+    #[test]
+    fn test_ec_level_ordering() {
+        assert!(QrEcLevel::L < QrEcLevel::M);
+        assert!(QrEcLevel::Q < QrEcLevel::H);
+    }
+}
@@ -1,22 +1,25 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+use crate::metrics;
+
 /// A simple in-memory rate limiter using a fixed window algorithm.
-/// Limits requests per IP address to prevent abuse.
+/// Limits requests per key (by default the client IP address) to prevent abuse.
 #[derive(Clone)]
-pub struct RateLimiter {
+pub struct RateLimiter<K = IpAddr> {
     /// Maximum requests per window
     limit: u32,
     /// Window duration
     window: Duration,
-    /// IP -> (count, window_start)
-    state: Arc<RwLock<HashMap<IpAddr, (u32, Instant)>>>,
+    /// Key -> (count, window_start)
+    state: Arc<RwLock<HashMap<K, (u32, Instant)>>>,
 }
 
-impl RateLimiter {
+impl<K: Eq + Hash> RateLimiter<K> {
     /// Create a new rate limiter.
     pub fn new(limit_per_minute: u32) -> Self {
         Self {
@@ -26,13 +29,13 @@ impl RateLimiter {
         }
     }
 
-    /// Check if a request is allowed for the given IP address.
+    /// Check if a request is allowed for the given key, counting it.
     /// Returns Ok(remaining) if allowed, Err(retry_after_secs) if rate limited.
-    pub async fn check(&self, ip: IpAddr) -> Result<u32, u64> {
+    pub async fn check(&self, key: K) -> Result<u32, u64> {
         let now = Instant::now();
         let mut state = self.state.write().await;
 
-        let entry = state.entry(ip).or_insert((0, now));
+        let entry = state.entry(key).or_insert((0, now));
 
         // Check if we need to reset the window
         if now.duration_since(entry.1) >= self.window {
@@ -41,8 +44,8 @@ impl RateLimiter {
         }
 
         if entry.0 >= self.limit {
-            let retry_after = self.window.as_secs() - now.duration_since(entry.1).as_secs();
-            return Err(retry_after.max(1));
+            metrics::RATE_LIMIT_REJECTIONS.inc();
+            return Err(self.retry_after(entry.1, now));
         }
 
         entry.0 += 1;
@@ -50,6 +53,30 @@ impl RateLimiter {
 
         Ok(remaining)
     }
+
+    /// Check if a request would be allowed for the given key, without counting it.
+    /// Used to count only some outcomes, such as failed password attempts.
+    pub async fn peek(&self, key: &K) -> Result<u32, u64> {
+        let now = Instant::now();
+        let state = self.state.read().await;
+
+        match state.get(key) {
+            Some(&(count, start)) if now.duration_since(start) < self.window => {
+                if count >= self.limit {
+                    metrics::RATE_LIMIT_REJECTIONS.inc();
+                    Err(self.retry_after(start, now))
+                } else {
+                    Ok(self.limit - count)
+                }
+            }
+            _ => Ok(self.limit),
+        }
+    }
+
+    fn retry_after(&self, window_start: Instant, now: Instant) -> u64 {
+        let retry_after = self.window.as_secs() - now.duration_since(window_start).as_secs();
+        retry_after.max(1)
+    }
 }
 
 #[cfg(test)]
@@ -125,4 +152,16 @@ mod tests {
             retry_after
         );
     }
+
+    #[tokio::test]
+    async fn test_rate_limiter_peek_does_not_count() {
+        let limiter: RateLimiter<&str> = RateLimiter::new(1);
+
+        assert_eq!(limiter.peek(&"link").await, Ok(1));
+        assert_eq!(limiter.peek(&"link").await, Ok(1));
+
+        limiter.check("link").await.unwrap();
+        assert!(limiter.peek(&"link").await.is_err());
+        assert!(limiter.peek(&"other").await.is_ok());
+    }
 }
@@ -1,13 +1,98 @@
+#[path = "repository/api_key_repository.rs"]
+mod api_key_repository;
+#[path = "repository/click_repository.rs"]
+mod click_repository;
 #[path = "repository/link_repository.rs"]
 mod link_repository;
+#[path = "repository/memory_link_repository.rs"]
+mod memory_link_repository;
+#[cfg(feature = "postgres")]
+#[path = "repository/postgres_api_key_repository.rs"]
+mod postgres_api_key_repository;
+#[cfg(feature = "postgres")]
+#[path = "repository/postgres_click_repository.rs"]
+mod postgres_click_repository;
+#[cfg(feature = "postgres")]
+#[path = "repository/postgres_link_repository.rs"]
+mod postgres_link_repository;
+#[cfg(feature = "postgres")]
+#[path = "repository/postgres_redirect_rule_repository.rs"]
+mod postgres_redirect_rule_repository;
+#[path = "repository/redirect_rule_repository.rs"]
+mod redirect_rule_repository;
 
-pub use link_repository::LinkRepository;
+pub use api_key_repository::{ApiKeyRepository, SqliteApiKeyRepository};
+pub use click_repository::{ClickRepository, SqliteClickRepository};
+pub use link_repository::{LinkRepository, SqliteLinkRepository};
+pub use memory_link_repository::MemoryLinkRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_api_key_repository::PgApiKeyRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_click_repository::PgClickRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_link_repository::PgLinkRepository;
+#[cfg(feature = "postgres")]
+pub use postgres_redirect_rule_repository::PgRedirectRuleRepository;
+pub use redirect_rule_repository::{RedirectRuleRepository, SqliteRedirectRuleRepository};
+
+use std::sync::Arc;
 
 use sqlx::sqlite::SqlitePool;
 
 /// Database pool type alias.
 pub type DbPool = SqlitePool;
 
+/// PostgreSQL pool type alias.
+#[cfg(feature = "postgres")]
+pub type PgDbPool = sqlx::postgres::PgPool;
+
+/// The repositories of one storage backend.
+#[derive(Clone)]
+pub struct Repositories {
+    pub links: Arc<dyn LinkRepository>,
+    pub clicks: Arc<dyn ClickRepository>,
+    pub api_keys: Arc<dyn ApiKeyRepository>,
+    pub rules: Arc<dyn RedirectRuleRepository>,
+}
+
+impl Repositories {
+    /// SQLite repositories sharing one pool.
+    pub fn sqlite(pool: DbPool) -> Self {
+        Self {
+            links: Arc::new(SqliteLinkRepository::new(pool.clone())),
+            clicks: Arc::new(SqliteClickRepository::new(pool.clone())),
+            api_keys: Arc::new(SqliteApiKeyRepository::new(pool.clone())),
+            rules: Arc::new(SqliteRedirectRuleRepository::new(pool)),
+        }
+    }
+
+    /// PostgreSQL repositories sharing one pool.
+    #[cfg(feature = "postgres")]
+    pub fn postgres(pool: PgDbPool) -> Self {
+        Self {
+            links: Arc::new(PgLinkRepository::new(pool.clone())),
+            clicks: Arc::new(PgClickRepository::new(pool.clone())),
+            api_keys: Arc::new(PgApiKeyRepository::new(pool.clone())),
+            rules: Arc::new(PgRedirectRuleRepository::new(pool)),
+        }
+    }
+}
+
+/// Connect to the backend selected by the `DATABASE_URL` scheme and run its migrations.
+pub async fn connect(database_url: &str) -> Result<Repositories, sqlx::Error> {
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Repositories::postgres(init_pg_db(database_url).await?));
+
+        #[cfg(not(feature = "postgres"))]
+        return Err(sqlx::Error::Configuration(
+            "PostgreSQL support requires building with the `postgres` feature".into(),
+        ));
+    }
+
+    Ok(Repositories::sqlite(init_db(database_url).await?))
+}
+
 /// Initialize the database pool and run migrations.
 pub async fn init_db(database_url: &str) -> Result<DbPool, sqlx::Error> {
     // Ensure the data directory exists
@@ -25,3 +110,13 @@ pub async fn init_db(database_url: &str) -> Result<DbPool, sqlx::Error> {
 
     Ok(pool)
 }
+
+/// Initialize the PostgreSQL pool and run its migrations.
+#[cfg(feature = "postgres")]
+pub async fn init_pg_db(database_url: &str) -> Result<PgDbPool, sqlx::Error> {
+    let pool = PgDbPool::connect(database_url).await?;
+
+    sqlx::migrate!("./migrations/postgres").run(&pool).await?;
+
+    Ok(pool)
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::domain::{ApiKey, Scope};
+use crate::error::{AppError, AppResult};
+
+use super::DbPool;
+
+/// Storage operations for API keys, implemented by each backend.
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// Store a new API key.
+    async fn create(&self, key: &ApiKey) -> AppResult<()>;
+
+    /// Find an API key by its public prefix.
+    async fn find_by_prefix(&self, prefix: &str) -> AppResult<Option<ApiKey>>;
+
+    /// List all API keys, newest first.
+    async fn list_all(&self) -> AppResult<Vec<ApiKey>>;
+
+    /// Mark an API key as revoked. Returns true if an active key was revoked.
+    async fn revoke(&self, id: Uuid, revoked_at: DateTime<Utc>) -> AppResult<bool>;
+}
+
+/// SQLite-backed API key repository.
+#[derive(Clone)]
+pub struct SqliteApiKeyRepository {
+    pool: DbPool,
+}
+
+impl SqliteApiKeyRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_api_key(&self, row: sqlx::sqlite::SqliteRow) -> AppResult<ApiKey> {
+        let id_str: String = row.get("id");
+        let id = Uuid::parse_str(&id_str)
+            .map_err(|e| AppError::Internal(format!("Invalid UUID in database: {}", e)))?;
+
+        let scopes_str: String = row.get("scopes");
+        let scopes = scopes_str
+            .split_whitespace()
+            .map(|s| {
+                Scope::parse(s)
+                    .ok_or_else(|| AppError::Internal(format!("Invalid scope in database: {}", s)))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let parse_datetime = |s: String| {
+            DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| AppError::Internal(format!("Invalid datetime in database: {}", e)))
+        };
+
+        Ok(ApiKey {
+            id,
+            name: row.get("name"),
+            prefix: row.get("prefix"),
+            key_hash: row.get("key_hash"),
+            scopes,
+            created_at: parse_datetime(row.get("created_at"))?,
+            expires_at: row
+                .get::<Option<String>, _>("expires_at")
+                .map(parse_datetime)
+                .transpose()?,
+            revoked_at: row
+                .get::<Option<String>, _>("revoked_at")
+                .map(parse_datetime)
+                .transpose()?,
+        })
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for SqliteApiKeyRepository {
+    async fn create(&self, key: &ApiKey) -> AppResult<()> {
+        let scopes = key
+            .scopes
+            .iter()
+            .map(Scope::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, name, prefix, key_hash, scopes, created_at, expires_at, revoked_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(key.id.to_string())
+        .bind(&key.name)
+        .bind(&key.prefix)
+        .bind(&key.key_hash)
+        .bind(&scopes)
+        .bind(key.created_at.to_rfc3339())
+        .bind(key.expires_at.map(|e| e.to_rfc3339()))
+        .bind(key.revoked_at.map(|e| e.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_prefix(&self, prefix: &str) -> AppResult<Option<ApiKey>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, prefix, key_hash, scopes, created_at, expires_at, revoked_at
+            FROM api_keys
+            WHERE prefix = ?
+            "#,
+        )
+        .bind(prefix)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_api_key(row)).transpose()
+    }
+
+    async fn list_all(&self) -> AppResult<Vec<ApiKey>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, prefix, key_hash, scopes, created_at, expires_at, revoked_at
+            FROM api_keys
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_api_key(row))
+            .collect()
+    }
+
+    async fn revoke(&self, id: Uuid, revoked_at: DateTime<Utc>) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = ?
+            WHERE id = ? AND revoked_at IS NULL
+            "#,
+        )
+        .bind(revoked_at.to_rfc3339())
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
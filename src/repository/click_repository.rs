@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::domain::{CampaignStats, Click, DailyClicks, LinkStats, VariantClicks};
+use crate::error::{AppError, AppResult};
+
+use super::DbPool;
+
+/// Storage operations for click events, implemented by each backend.
+#[async_trait]
+pub trait ClickRepository: Send + Sync {
+    /// Insert a batch of click events in a single transaction.
+    /// Clicks for links deleted in the meantime are silently skipped.
+    async fn insert_batch(&self, clicks: &[Click]) -> AppResult<u64>;
+
+    /// Compute total clicks, unique visitors and the per-day series for a link.
+    async fn stats(&self, link_id: Uuid) -> AppResult<LinkStats>;
+
+    /// Compute the same totals and series across all links tagged with a UTM campaign.
+    async fn campaign_stats(&self, campaign: &str) -> AppResult<CampaignStats>;
+}
+
+/// SQLite-backed click repository.
+#[derive(Clone)]
+pub struct SqliteClickRepository {
+    pool: DbPool,
+}
+
+impl SqliteClickRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ClickRepository for SqliteClickRepository {
+    async fn insert_batch(&self, clicks: &[Click]) -> AppResult<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+
+        for click in clicks {
+            let link_id_str = click.link_id.to_string();
+            let result = sqlx::query(
+                r#"
+                INSERT INTO clicks (link_id, clicked_at, referrer, user_agent, ip_hash, variant)
+                SELECT ?, ?, ?, ?, ?, ?
+                WHERE EXISTS (SELECT 1 FROM links WHERE id = ?)
+                "#,
+            )
+            .bind(&link_id_str)
+            .bind(click.clicked_at.to_rfc3339())
+            .bind(&click.referrer)
+            .bind(&click.user_agent)
+            .bind(&click.ip_hash)
+            .bind(&click.variant)
+            .bind(&link_id_str)
+            .execute(&mut *tx)
+            .await?;
+
+            inserted += result.rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn stats(&self, link_id: Uuid) -> AppResult<LinkStats> {
+        let link_id_str = link_id.to_string();
+
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS clicks, COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            WHERE link_id = ?
+            "#,
+        )
+        .bind(&link_id_str)
+        .fetch_one(&self.pool)
+        .await?;
+
+        // clicked_at is stored as RFC 3339 in UTC, so its first 10 characters are the day
+        let rows = sqlx::query(
+            r#"
+            SELECT substr(clicked_at, 1, 10) AS day,
+                   COUNT(*) AS clicks,
+                   COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            WHERE link_id = ?
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(&link_id_str)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let daily = daily_clicks(rows)?;
+
+        let variants = sqlx::query(
+            r#"
+            SELECT variant, COUNT(*) AS clicks, COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            WHERE link_id = ? AND variant IS NOT NULL
+            GROUP BY variant
+            ORDER BY variant ASC
+            "#,
+        )
+        .bind(&link_id_str)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| VariantClicks {
+            variant: row.get("variant"),
+            clicks: row.get::<i64, _>("clicks") as u64,
+            unique_visitors: row.get::<i64, _>("unique_visitors") as u64,
+        })
+        .collect();
+
+        Ok(LinkStats {
+            link_id,
+            total_clicks: totals.get::<i64, _>("clicks") as u64,
+            unique_visitors: totals.get::<i64, _>("unique_visitors") as u64,
+            daily,
+            variants,
+        })
+    }
+
+    async fn campaign_stats(&self, campaign: &str) -> AppResult<CampaignStats> {
+        let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM links WHERE utm_campaign = ?")
+            .bind(campaign)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS clicks, COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            JOIN links ON links.id = clicks.link_id
+            WHERE links.utm_campaign = ?
+            "#,
+        )
+        .bind(campaign)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT substr(clicks.clicked_at, 1, 10) AS day,
+                   COUNT(*) AS clicks,
+                   COUNT(DISTINCT clicks.ip_hash) AS unique_visitors
+            FROM clicks
+            JOIN links ON links.id = clicks.link_id
+            WHERE links.utm_campaign = ?
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(campaign)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(CampaignStats {
+            campaign: campaign.to_string(),
+            links: links as u64,
+            total_clicks: totals.get::<i64, _>("clicks") as u64,
+            unique_visitors: totals.get::<i64, _>("unique_visitors") as u64,
+            daily: daily_clicks(rows)?,
+        })
+    }
+}
+
+/// Convert per-day rows (`day` as YYYY-MM-DD) into the daily series.
+fn daily_clicks(rows: Vec<sqlx::sqlite::SqliteRow>) -> AppResult<Vec<DailyClicks>> {
+    rows.into_iter()
+        .map(|row| {
+            let day: String = row.get("day");
+            let date = NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                .map_err(|e| AppError::Internal(format!("Invalid click date: {}", e)))?;
+            Ok(DailyClicks {
+                date,
+                clicks: row.get::<i64, _>("clicks") as u64,
+                unique_visitors: row.get::<i64, _>("unique_visitors") as u64,
+            })
+        })
+        .collect()
+}
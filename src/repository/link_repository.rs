@@ -1,79 +1,202 @@
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::Row;
+use sqlx::{QueryBuilder, Row, Sqlite};
 use url::Url;
 use uuid::Uuid;
 
-use crate::domain::{Link, ShortCode};
+use crate::domain::{
+    Link, LinkQuery, LinkSort, LinkStatus, NEVER_EXPIRES_SORT_KEY, Passthrough, RedirectType,
+    ShortCode, SortOrder, Utm, Variant,
+};
 use crate::error::{AppError, AppResult};
 
 use super::DbPool;
 
-/// Repository for link persistence operations.
+/// Columns read into a [`Link`], shared by the SQL backends.
+pub(super) const LINK_COLUMNS: &str = "id, short_code, target_url, created_at, updated_at, expires_at, password_hash, max_clicks, click_count, active_from, prelaunch_url, variants, redirect_type, passthrough, interstitial";
+
+/// Storage operations for links, implemented by each backend.
+#[async_trait]
+pub trait LinkRepository: Send + Sync {
+    /// Store a new link. Returns the stored link or an error if its short code already exists.
+    async fn create(&self, link: &Link) -> AppResult<Link>;
+
+    /// Find a link by its ID.
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Link>>;
+
+    /// Find a link by its short code.
+    async fn find_by_short_code(&self, short_code: &str) -> AppResult<Option<Link>>;
+
+    /// List links matching the query's filters, in the requested order,
+    /// starting after the query's cursor. Returns at most `limit` links.
+    async fn list(&self, query: &LinkQuery, now: DateTime<Utc>) -> AppResult<Vec<Link>>;
+
+    /// Update the mutable fields (target URL, expiry, variants, redirect type, passthrough,
+    /// interstitial, updated_at) of a link.
+    /// Returns true if a link was updated.
+    async fn update(&self, link: &Link) -> AppResult<bool>;
+
+    /// Delete a link by its ID. Returns true if a link was deleted.
+    async fn delete(&self, id: Uuid) -> AppResult<bool>;
+
+    /// Count one redirect of a click-limited link, in a single atomic update so that
+    /// concurrent redirects cannot overshoot the limit. Returns false if the link is
+    /// unlimited, used up, expired at `now` or gone.
+    async fn consume_click(&self, id: Uuid, now: DateTime<Utc>) -> AppResult<bool>;
+
+    /// Delete all expired and used-up links.
+    async fn delete_expired(&self) -> AppResult<u64>;
+}
+
+/// SQLite-backed link repository.
 #[derive(Clone)]
-pub struct LinkRepository {
+pub struct SqliteLinkRepository {
     pool: DbPool,
 }
 
-impl LinkRepository {
+impl SqliteLinkRepository {
     pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
 
-    /// Create a new link. Returns the created link or an error if short_code already exists.
-    pub async fn create(
-        &self,
-        id: Uuid,
-        short_code: &ShortCode,
-        target_url: &Url,
-        created_at: DateTime<Utc>,
-        expires_at: Option<DateTime<Utc>>,
-    ) -> AppResult<Link> {
-        let id_str = id.to_string();
-        let short_code_str = short_code.as_str();
-        let target_url_str = target_url.to_string();
-        let created_at_str = created_at.to_rfc3339();
-        let expires_at_str = expires_at.map(|e| e.to_rfc3339());
+    fn row_to_link(&self, row: sqlx::sqlite::SqliteRow) -> AppResult<Link> {
+        let id_str: String = row.get("id");
+        let id = Uuid::parse_str(&id_str)
+            .map_err(|e| AppError::Internal(format!("Invalid UUID in database: {}", e)))?;
+
+        let short_code: String = row.get("short_code");
+        let target_url_str: String = row.get("target_url");
+        let target_url = Url::parse(&target_url_str)
+            .map_err(|e| AppError::Internal(format!("Invalid URL in database: {}", e)))?;
+
+        let created_at_str: String = row.get("created_at");
+        let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|e| AppError::Internal(format!("Invalid datetime in database: {}", e)))?
+            .with_timezone(&Utc);
+
+        let updated_at_str: String = row.get("updated_at");
+        let updated_at = DateTime::parse_from_rfc3339(&updated_at_str)
+            .map_err(|e| AppError::Internal(format!("Invalid datetime in database: {}", e)))?
+            .with_timezone(&Utc);
+
+        let expires_at_str: Option<String> = row.get("expires_at");
+        let expires_at = expires_at_str
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|e| AppError::Internal(format!("Invalid expiry datetime: {}", e)))
+            })
+            .transpose()?;
+
+        let active_from_str: Option<String> = row.get("active_from");
+        let active_from = active_from_str
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|e| AppError::Internal(format!("Invalid activation datetime: {}", e)))
+            })
+            .transpose()?;
+
+        let prelaunch_url_str: Option<String> = row.get("prelaunch_url");
+        let prelaunch_url = prelaunch_url_str
+            .map(|s| Url::parse(&s))
+            .transpose()
+            .map_err(|e| AppError::Internal(format!("Invalid URL in database: {}", e)))?;
+
+        Ok(Link {
+            id,
+            short_code: ShortCode::from_existing(short_code),
+            target_url,
+            created_at,
+            updated_at,
+            expires_at,
+            password_hash: row.get("password_hash"),
+            max_clicks: row.get::<Option<i64>, _>("max_clicks").map(|n| n as u32),
+            click_count: row.get::<i64, _>("click_count") as u32,
+            active_from,
+            prelaunch_url,
+            variants: parse_variants(row.get("variants"))?,
+            redirect_type: parse_redirect_type(row.get("redirect_type"))?,
+            passthrough: parse_passthrough(row.get("passthrough"))?,
+            interstitial: row.get("interstitial"),
+        })
+    }
+}
+
+#[async_trait]
+impl LinkRepository for SqliteLinkRepository {
+    async fn create(&self, link: &Link) -> AppResult<Link> {
+        let id_str = link.id.to_string();
+        let short_code_str = link.short_code.as_str();
+        let target_url_str = link.target_url.to_string();
+        let target_host = target_host(&link.target_url);
+        let utm = Utm::from_url(&link.target_url);
+        let created_at_str = link.created_at.to_rfc3339();
+        let updated_at_str = link.updated_at.to_rfc3339();
+        let expires_at_str = link.expires_at.map(|e| e.to_rfc3339());
+        let active_from_str = link.active_from.map(|a| a.to_rfc3339());
+        let prelaunch_url_str = link.prelaunch_url.as_ref().map(|u| u.to_string());
+        let variants_str = variants_json(&link.variants)?;
 
         sqlx::query(
             r#"
-            INSERT INTO links (id, short_code, target_url, created_at, expires_at)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO links (id, short_code, target_url, target_host, created_at, updated_at, expires_at, password_hash, max_clicks, click_count, active_from, prelaunch_url, variants, redirect_type, passthrough, utm_source, utm_medium, utm_campaign, utm_term, utm_content, interstitial)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&id_str)
         .bind(short_code_str)
         .bind(&target_url_str)
+        .bind(&target_host)
         .bind(&created_at_str)
+        .bind(&updated_at_str)
         .bind(&expires_at_str)
+        .bind(&link.password_hash)
+        .bind(link.max_clicks.map(i64::from))
+        .bind(i64::from(link.click_count))
+        .bind(&active_from_str)
+        .bind(&prelaunch_url_str)
+        .bind(&variants_str)
+        .bind(link.redirect_type.map(|r| r.as_str()))
+        .bind(passthrough_str(link.passthrough))
+        .bind(utm.source)
+        .bind(utm.medium)
+        .bind(utm.campaign)
+        .bind(utm.term)
+        .bind(utm.content)
+        .bind(link.interstitial)
         .execute(&self.pool)
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(ref db_err) = e
                 && db_err.message().contains("UNIQUE constraint failed")
             {
-                return AppError::ShortCodeExhausted;
+                return AppError::ShortCodeTaken(short_code_str.to_string());
             }
             AppError::Database(e)
         })?;
 
-        Ok(Link {
-            id,
-            short_code: short_code.clone(),
-            target_url: target_url.clone(),
-            created_at,
-            expires_at,
-        })
+        Ok(link.clone())
     }
 
-    /// Find a link by its short code.
-    pub async fn find_by_short_code(&self, short_code: &str) -> AppResult<Option<Link>> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, short_code, target_url, created_at, expires_at
-            FROM links
-            WHERE short_code = ?
-            "#,
-        )
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Link>> {
+        let id_str = id.to_string();
+        let row = sqlx::query(&format!("SELECT {} FROM links WHERE id = ?", LINK_COLUMNS))
+            .bind(&id_str)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(self.row_to_link(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_by_short_code(&self, short_code: &str) -> AppResult<Option<Link>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM links WHERE short_code = ?",
+            LINK_COLUMNS
+        ))
         .bind(short_code)
         .fetch_optional(&self.pool)
         .await?;
@@ -84,23 +207,148 @@ impl LinkRepository {
         }
     }
 
-    /// List all links (no filtering).
-    pub async fn list_all(&self) -> AppResult<Vec<Link>> {
-        let rows = sqlx::query(
+    async fn list(&self, query: &LinkQuery, now: DateTime<Utc>) -> AppResult<Vec<Link>> {
+        let sort_expr = match query.sort {
+            LinkSort::CreatedAt => "created_at".to_string(),
+            LinkSort::ExpiresAt => format!("COALESCE(expires_at, '{}')", NEVER_EXPIRES_SORT_KEY),
+            LinkSort::ShortCode => "short_code".to_string(),
+        };
+        let (cmp, direction) = match query.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new(format!("SELECT {} FROM links WHERE 1 = 1", LINK_COLUMNS));
+
+        match query.status {
+            Some(LinkStatus::Active) => {
+                builder
+                    .push(" AND (expires_at IS NULL OR expires_at > ")
+                    .push_bind(now.to_rfc3339())
+                    .push(") AND (max_clicks IS NULL OR click_count < max_clicks)")
+                    .push(" AND (active_from IS NULL OR active_from <= ")
+                    .push_bind(now.to_rfc3339())
+                    .push(")");
+            }
+            Some(LinkStatus::Expired) => {
+                builder
+                    .push(" AND ((expires_at IS NOT NULL AND expires_at <= ")
+                    .push_bind(now.to_rfc3339())
+                    .push(") OR click_count >= max_clicks)");
+            }
+            Some(LinkStatus::NeverExpires) => {
+                builder.push(" AND expires_at IS NULL");
+            }
+            Some(LinkStatus::Scheduled) => {
+                builder
+                    .push(" AND active_from > ")
+                    .push_bind(now.to_rfc3339());
+            }
+            None => {}
+        }
+
+        if let Some(after) = query.created_after {
+            builder
+                .push(" AND created_at >= ")
+                .push_bind(after.to_rfc3339());
+        }
+        if let Some(before) = query.created_before {
+            builder
+                .push(" AND created_at < ")
+                .push_bind(before.to_rfc3339());
+        }
+
+        if let Some(domain) = &query.domain {
+            // Subdomains match on the ".domain" suffix, without LIKE wildcards
+            let suffix = format!(".{}", domain);
+            builder
+                .push(" AND (target_host = ")
+                .push_bind(domain.clone())
+                .push(" OR substr(target_host, -")
+                .push_bind(suffix.len() as i64)
+                .push(") = ")
+                .push_bind(suffix)
+                .push(")");
+        }
+
+        if let Some(prefix) = &query.short_code_prefix {
+            // substr comparison is case-sensitive, unlike LIKE
+            builder
+                .push(" AND substr(short_code, 1, ")
+                .push_bind(prefix.len() as i64)
+                .push(") = ")
+                .push_bind(prefix.clone());
+        }
+
+        if let Some(campaign) = &query.campaign {
+            builder
+                .push(" AND utm_campaign = ")
+                .push_bind(campaign.clone());
+        }
+
+        if let Some(cursor) = &query.cursor {
+            builder
+                .push(format!(" AND ({} {} ", sort_expr, cmp))
+                .push_bind(cursor.key.clone())
+                .push(format!(" OR ({} = ", sort_expr))
+                .push_bind(cursor.key.clone())
+                .push(format!(" AND id {} ", cmp))
+                .push_bind(cursor.id.to_string())
+                .push("))");
+        }
+
+        builder
+            .push(format!(
+                " ORDER BY {} {}, id {} LIMIT ",
+                sort_expr, direction, direction
+            ))
+            .push_bind(query.limit as i64);
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        rows.into_iter().map(|row| self.row_to_link(row)).collect()
+    }
+
+    async fn update(&self, link: &Link) -> AppResult<bool> {
+        let id_str = link.id.to_string();
+        let target_url_str = link.target_url.to_string();
+        let target_host = target_host(&link.target_url);
+        let utm = Utm::from_url(&link.target_url);
+        let updated_at_str = link.updated_at.to_rfc3339();
+        let expires_at_str = link.expires_at.map(|e| e.to_rfc3339());
+        let variants_str = variants_json(&link.variants)?;
+
+        let result = sqlx::query(
             r#"
-            SELECT id, short_code, target_url, created_at, expires_at
-            FROM links
-            ORDER BY created_at DESC
+            UPDATE links
+            SET target_url = ?, target_host = ?, updated_at = ?, expires_at = ?, variants = ?,
+                redirect_type = ?, passthrough = ?, utm_source = ?, utm_medium = ?,
+                utm_campaign = ?, utm_term = ?, utm_content = ?, interstitial = ?
+            WHERE id = ?
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(&target_url_str)
+        .bind(&target_host)
+        .bind(&updated_at_str)
+        .bind(&expires_at_str)
+        .bind(&variants_str)
+        .bind(link.redirect_type.map(|r| r.as_str()))
+        .bind(passthrough_str(link.passthrough))
+        .bind(utm.source)
+        .bind(utm.medium)
+        .bind(utm.campaign)
+        .bind(utm.term)
+        .bind(utm.content)
+        .bind(link.interstitial)
+        .bind(&id_str)
+        .execute(&self.pool)
         .await?;
 
-        rows.into_iter().map(|row| self.row_to_link(row)).collect()
+        Ok(result.rows_affected() > 0)
     }
 
-    /// Delete a link by its ID. Returns true if a link was deleted.
-    pub async fn delete(&self, id: Uuid) -> AppResult<bool> {
+    async fn delete(&self, id: Uuid) -> AppResult<bool> {
         let id_str = id.to_string();
         let result = sqlx::query("DELETE FROM links WHERE id = ?")
             .bind(&id_str)
@@ -110,13 +358,32 @@ impl LinkRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Delete all expired links.
-    pub async fn delete_expired(&self) -> AppResult<u64> {
+    async fn consume_click(&self, id: Uuid, now: DateTime<Utc>) -> AppResult<bool> {
+        let id_str = id.to_string();
+        let now_str = now.to_rfc3339();
+        let result = sqlx::query(
+            r#"
+            UPDATE links
+            SET click_count = click_count + 1
+            WHERE id = ? AND click_count < max_clicks
+              AND (expires_at IS NULL OR expires_at > ?)
+            "#,
+        )
+        .bind(&id_str)
+        .bind(&now_str)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
         let now = Utc::now().to_rfc3339();
         let result = sqlx::query(
             r#"
             DELETE FROM links
-            WHERE expires_at IS NOT NULL AND expires_at < ?
+            WHERE (expires_at IS NOT NULL AND expires_at < ?)
+               OR click_count >= max_clicks
             "#,
         )
         .bind(&now)
@@ -125,37 +392,50 @@ impl LinkRepository {
 
         Ok(result.rows_affected())
     }
+}
 
-    fn row_to_link(&self, row: sqlx::sqlite::SqliteRow) -> AppResult<Link> {
-        let id_str: String = row.get("id");
-        let id = Uuid::parse_str(&id_str)
-            .map_err(|e| AppError::Internal(format!("Invalid UUID in database: {}", e)))?;
+/// Serialize a link's variants for storage, as NULL for links without a split.
+pub(super) fn variants_json(variants: &[Variant]) -> AppResult<Option<String>> {
+    if variants.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(variants)
+        .map(Some)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize variants: {}", e)))
+}
 
-        let short_code: String = row.get("short_code");
-        let target_url_str: String = row.get("target_url");
-        let target_url = Url::parse(&target_url_str)
-            .map_err(|e| AppError::Internal(format!("Invalid URL in database: {}", e)))?;
+/// Parse stored variants; NULL means no split.
+pub(super) fn parse_variants(stored: Option<String>) -> AppResult<Vec<Variant>> {
+    stored
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| AppError::Internal(format!("Invalid variants in database: {}", e)))
+}
 
-        let created_at_str: String = row.get("created_at");
-        let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-            .map_err(|e| AppError::Internal(format!("Invalid datetime in database: {}", e)))?
-            .with_timezone(&Utc);
+/// Parse a stored redirect type; NULL means the server default.
+pub(super) fn parse_redirect_type(stored: Option<String>) -> AppResult<Option<RedirectType>> {
+    stored
+        .map(|s| s.parse())
+        .transpose()
+        .map_err(|e| AppError::Internal(format!("Invalid redirect type in database: {}", e)))
+}
 
-        let expires_at_str: Option<String> = row.get("expires_at");
-        let expires_at = expires_at_str
-            .map(|s| {
-                DateTime::parse_from_rfc3339(&s)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .map_err(|e| AppError::Internal(format!("Invalid expiry datetime: {}", e)))
-            })
-            .transpose()?;
+/// Stored passthrough mode, NULL when off.
+pub(super) fn passthrough_str(passthrough: Passthrough) -> Option<&'static str> {
+    (!passthrough.is_off()).then(|| passthrough.as_str())
+}
 
-        Ok(Link {
-            id,
-            short_code: ShortCode::from_existing(short_code),
-            target_url,
-            created_at,
-            expires_at,
-        })
-    }
+/// Parse a stored passthrough mode; NULL means off.
+pub(super) fn parse_passthrough(stored: Option<String>) -> AppResult<Passthrough> {
+    stored
+        .map(|s| s.parse())
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| AppError::Internal(format!("Invalid passthrough in database: {}", e)))
+}
+
+/// Lowercased host of a target URL, stored for domain filtering.
+pub(super) fn target_host(url: &Url) -> String {
+    url.host_str().unwrap_or_default().to_lowercase()
 }
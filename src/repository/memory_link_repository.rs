@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::{Link, LinkQuery, LinkStatus, SortOrder, Utm};
+use crate::error::{AppError, AppResult};
+
+use super::LinkRepository;
+use super::link_repository::target_host;
+
+/// In-memory link repository, for tests and ephemeral setups.
+/// Mirrors the ordering and filtering semantics of the SQL backends.
+#[derive(Clone, Default)]
+pub struct MemoryLinkRepository {
+    links: Arc<RwLock<HashMap<Uuid, Link>>>,
+}
+
+impl MemoryLinkRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<Uuid, Link>> {
+        self.links.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<Uuid, Link>> {
+        self.links.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Check whether a link passes the query's filters (cursor excluded).
+fn matches(link: &Link, query: &LinkQuery, now: DateTime<Utc>) -> bool {
+    let status_ok = match query.status {
+        Some(LinkStatus::Active) => {
+            !link.is_expired(now) && !link.is_exhausted() && !link.is_scheduled(now)
+        }
+        Some(LinkStatus::Expired) => link.is_expired(now) || link.is_exhausted(),
+        Some(LinkStatus::NeverExpires) => link.expires_at.is_none(),
+        Some(LinkStatus::Scheduled) => link.is_scheduled(now),
+        None => true,
+    };
+
+    let host = target_host(&link.target_url);
+    let domain_ok = query.domain.as_ref().is_none_or(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|rest| rest.ends_with('.'))
+    });
+
+    status_ok
+        && domain_ok
+        && query
+            .created_after
+            .is_none_or(|after| link.created_at >= after)
+        && query
+            .created_before
+            .is_none_or(|before| link.created_at < before)
+        && query
+            .short_code_prefix
+            .as_ref()
+            .is_none_or(|prefix| link.short_code.as_str().starts_with(prefix.as_str()))
+        && query.campaign.as_ref().is_none_or(|campaign| {
+            Utm::from_url(&link.target_url).campaign.as_ref() == Some(campaign)
+        })
+}
+
+#[async_trait]
+impl LinkRepository for MemoryLinkRepository {
+    async fn create(&self, link: &Link) -> AppResult<Link> {
+        let mut links = self.write();
+
+        if links.values().any(|l| l.short_code == link.short_code) {
+            return Err(AppError::ShortCodeTaken(
+                link.short_code.as_str().to_string(),
+            ));
+        }
+        links.insert(link.id, link.clone());
+
+        Ok(link.clone())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Link>> {
+        Ok(self.read().get(&id).cloned())
+    }
+
+    async fn find_by_short_code(&self, short_code: &str) -> AppResult<Option<Link>> {
+        Ok(self
+            .read()
+            .values()
+            .find(|l| l.short_code.as_str() == short_code)
+            .cloned())
+    }
+
+    async fn list(&self, query: &LinkQuery, now: DateTime<Utc>) -> AppResult<Vec<Link>> {
+        let mut links: Vec<(String, Link)> = self
+            .read()
+            .values()
+            .filter(|l| matches(l, query, now))
+            .map(|l| (query.sort.key_of(l), l.clone()))
+            .collect();
+
+        links.sort_by(|(a_key, a), (b_key, b)| a_key.cmp(b_key).then(a.id.cmp(&b.id)));
+        if query.order == SortOrder::Desc {
+            links.reverse();
+        }
+
+        Ok(links
+            .into_iter()
+            .filter(|(key, link)| match &query.cursor {
+                Some(cursor) => {
+                    let position = (key.as_str(), link.id).cmp(&(cursor.key.as_str(), cursor.id));
+                    match query.order {
+                        SortOrder::Asc => position.is_gt(),
+                        SortOrder::Desc => position.is_lt(),
+                    }
+                }
+                None => true,
+            })
+            .take(query.limit as usize)
+            .map(|(_, link)| link)
+            .collect())
+    }
+
+    async fn update(&self, link: &Link) -> AppResult<bool> {
+        let mut links = self.write();
+        match links.get_mut(&link.id) {
+            Some(existing) => {
+                existing.target_url = link.target_url.clone();
+                existing.updated_at = link.updated_at;
+                existing.expires_at = link.expires_at;
+                existing.variants = link.variants.clone();
+                existing.redirect_type = link.redirect_type;
+                existing.passthrough = link.passthrough;
+                existing.interstitial = link.interstitial;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, id: Uuid) -> AppResult<bool> {
+        Ok(self.write().remove(&id).is_some())
+    }
+
+    async fn consume_click(&self, id: Uuid, now: DateTime<Utc>) -> AppResult<bool> {
+        // The write lock makes the check and the increment atomic
+        let mut links = self.write();
+        match links.get_mut(&id) {
+            Some(link)
+                if link.max_clicks.is_some() && !link.is_exhausted() && !link.is_expired(now) =>
+            {
+                link.click_count += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
+        let now = Utc::now();
+        let mut links = self.write();
+        let before = links.len();
+        links.retain(|_, l| l.expires_at.is_none_or(|exp| exp >= now) && !l.is_exhausted());
+        Ok((before - links.len()) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LinkCursor, LinkSort, ShortCode};
+    use chrono::Duration;
+    use url::Url;
+
+    async fn create(repo: &MemoryLinkRepository, code: &str, url: &str) -> Link {
+        repo.create(&Link::new(
+            ShortCode::from_existing(code.to_string()),
+            Url::parse(url).unwrap(),
+            Utc::now(),
+        ))
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_taken_short_code() {
+        let repo = MemoryLinkRepository::new();
+        create(&repo, "promo", "https://example.com").await;
+
+        let result = repo
+            .create(&Link::new(
+                ShortCode::from_existing("promo".to_string()),
+                Url::parse("https://example.org").unwrap(),
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(result, Err(AppError::ShortCodeTaken(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_and_cursor() {
+        let repo = MemoryLinkRepository::new();
+        create(&repo, "alpha", "https://shop.example.com").await;
+        create(&repo, "bravo", "https://notexample.com").await;
+        create(&repo, "charlie", "https://example.com").await;
+
+        let query = LinkQuery {
+            domain: Some("example.com".to_string()),
+            sort: LinkSort::ShortCode,
+            order: SortOrder::Asc,
+            limit: 1,
+            ..LinkQuery::default()
+        };
+        let first = repo.list(&query, Utc::now()).await.unwrap();
+        assert_eq!(first[0].short_code.as_str(), "alpha");
+
+        let next = LinkQuery {
+            cursor: Some(LinkCursor::after(&first[0], LinkSort::ShortCode)),
+            ..query
+        };
+        let second = repo.list(&next, Utc::now()).await.unwrap();
+        assert_eq!(second[0].short_code.as_str(), "charlie");
+    }
+
+    #[tokio::test]
+    async fn test_delete_expired() {
+        let repo = MemoryLinkRepository::new();
+        let now = Utc::now();
+        repo.create(&Link {
+            expires_at: Some(now - Duration::hours(1)),
+            ..Link::new(
+                ShortCode::from_existing("old".to_string()),
+                Url::parse("https://example.com").unwrap(),
+                now,
+            )
+        })
+        .await
+        .unwrap();
+        let kept = create(&repo, "new", "https://example.com").await;
+
+        assert_eq!(repo.delete_expired().await.unwrap(), 1);
+        assert!(repo.find_by_id(kept.id).await.unwrap().is_some());
+        assert!(repo.find_by_short_code("old").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_consume_click_stops_at_limit() {
+        let repo = MemoryLinkRepository::new();
+        let now = Utc::now();
+        let unlimited = create(&repo, "open", "https://example.com").await;
+        let limited = repo
+            .create(&Link {
+                max_clicks: Some(2),
+                ..Link::new(
+                    ShortCode::from_existing("twice".to_string()),
+                    Url::parse("https://example.com").unwrap(),
+                    now,
+                )
+            })
+            .await
+            .unwrap();
+
+        assert!(!repo.consume_click(unlimited.id, now).await.unwrap());
+        assert!(repo.consume_click(limited.id, now).await.unwrap());
+        assert!(repo.consume_click(limited.id, now).await.unwrap());
+        assert!(!repo.consume_click(limited.id, now).await.unwrap());
+
+        assert_eq!(repo.delete_expired().await.unwrap(), 1);
+        assert!(repo.find_by_id(unlimited.id).await.unwrap().is_some());
+    }
+}
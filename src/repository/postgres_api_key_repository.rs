@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::domain::{ApiKey, Scope};
+use crate::error::{AppError, AppResult};
+
+use super::{ApiKeyRepository, PgDbPool};
+
+/// PostgreSQL-backed API key repository.
+#[derive(Clone)]
+pub struct PgApiKeyRepository {
+    pool: PgDbPool,
+}
+
+impl PgApiKeyRepository {
+    pub fn new(pool: PgDbPool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_api_key(&self, row: PgRow) -> AppResult<ApiKey> {
+        let scopes_str: String = row.get("scopes");
+        let scopes = scopes_str
+            .split_whitespace()
+            .map(|s| {
+                Scope::parse(s)
+                    .ok_or_else(|| AppError::Internal(format!("Invalid scope in database: {}", s)))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(ApiKey {
+            id: row.get("id"),
+            name: row.get("name"),
+            prefix: row.get("prefix"),
+            key_hash: row.get("key_hash"),
+            scopes,
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+            revoked_at: row.get("revoked_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PgApiKeyRepository {
+    async fn create(&self, key: &ApiKey) -> AppResult<()> {
+        let scopes = key
+            .scopes
+            .iter()
+            .map(Scope::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, name, prefix, key_hash, scopes, created_at, expires_at, revoked_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(key.id)
+        .bind(&key.name)
+        .bind(&key.prefix)
+        .bind(&key.key_hash)
+        .bind(&scopes)
+        .bind(key.created_at)
+        .bind(key.expires_at)
+        .bind(key.revoked_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_prefix(&self, prefix: &str) -> AppResult<Option<ApiKey>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, prefix, key_hash, scopes, created_at, expires_at, revoked_at
+            FROM api_keys
+            WHERE prefix = $1
+            "#,
+        )
+        .bind(prefix)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_api_key(row)).transpose()
+    }
+
+    async fn list_all(&self) -> AppResult<Vec<ApiKey>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, prefix, key_hash, scopes, created_at, expires_at, revoked_at
+            FROM api_keys
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_api_key(row))
+            .collect()
+    }
+
+    async fn revoke(&self, id: Uuid, revoked_at: DateTime<Utc>) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = $1
+            WHERE id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(revoked_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
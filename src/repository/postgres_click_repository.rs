@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::domain::{CampaignStats, Click, DailyClicks, LinkStats, VariantClicks};
+use crate::error::AppResult;
+
+use super::{ClickRepository, PgDbPool};
+
+/// PostgreSQL-backed click repository.
+#[derive(Clone)]
+pub struct PgClickRepository {
+    pool: PgDbPool,
+}
+
+impl PgClickRepository {
+    pub fn new(pool: PgDbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ClickRepository for PgClickRepository {
+    async fn insert_batch(&self, clicks: &[Click]) -> AppResult<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+
+        for click in clicks {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO clicks (link_id, clicked_at, referrer, user_agent, ip_hash, variant)
+                SELECT $1, $2, $3, $4, $5, $6
+                WHERE EXISTS (SELECT 1 FROM links WHERE id = $1)
+                "#,
+            )
+            .bind(click.link_id)
+            .bind(click.clicked_at)
+            .bind(&click.referrer)
+            .bind(&click.user_agent)
+            .bind(&click.ip_hash)
+            .bind(&click.variant)
+            .execute(&mut *tx)
+            .await?;
+
+            inserted += result.rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn stats(&self, link_id: Uuid) -> AppResult<LinkStats> {
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS clicks, COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            WHERE link_id = $1
+            "#,
+        )
+        .bind(link_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT (clicked_at AT TIME ZONE 'UTC')::date AS day,
+                   COUNT(*) AS clicks,
+                   COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            WHERE link_id = $1
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(link_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let daily = daily_clicks(rows);
+
+        let variants = sqlx::query(
+            r#"
+            SELECT variant, COUNT(*) AS clicks, COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            WHERE link_id = $1 AND variant IS NOT NULL
+            GROUP BY variant
+            ORDER BY variant ASC
+            "#,
+        )
+        .bind(link_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| VariantClicks {
+            variant: row.get("variant"),
+            clicks: row.get::<i64, _>("clicks") as u64,
+            unique_visitors: row.get::<i64, _>("unique_visitors") as u64,
+        })
+        .collect();
+
+        Ok(LinkStats {
+            link_id,
+            total_clicks: totals.get::<i64, _>("clicks") as u64,
+            unique_visitors: totals.get::<i64, _>("unique_visitors") as u64,
+            daily,
+            variants,
+        })
+    }
+
+    async fn campaign_stats(&self, campaign: &str) -> AppResult<CampaignStats> {
+        let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM links WHERE utm_campaign = $1")
+            .bind(campaign)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS clicks, COUNT(DISTINCT ip_hash) AS unique_visitors
+            FROM clicks
+            JOIN links ON links.id = clicks.link_id
+            WHERE links.utm_campaign = $1
+            "#,
+        )
+        .bind(campaign)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT (clicks.clicked_at AT TIME ZONE 'UTC')::date AS day,
+                   COUNT(*) AS clicks,
+                   COUNT(DISTINCT clicks.ip_hash) AS unique_visitors
+            FROM clicks
+            JOIN links ON links.id = clicks.link_id
+            WHERE links.utm_campaign = $1
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(campaign)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(CampaignStats {
+            campaign: campaign.to_string(),
+            links: links as u64,
+            total_clicks: totals.get::<i64, _>("clicks") as u64,
+            unique_visitors: totals.get::<i64, _>("unique_visitors") as u64,
+            daily: daily_clicks(rows),
+        })
+    }
+}
+
+/// Convert per-day rows into the daily series.
+fn daily_clicks(rows: Vec<sqlx::postgres::PgRow>) -> Vec<DailyClicks> {
+    rows.into_iter()
+        .map(|row| DailyClicks {
+            date: row.get::<NaiveDate, _>("day"),
+            clicks: row.get::<i64, _>("clicks") as u64,
+            unique_visitors: row.get::<i64, _>("unique_visitors") as u64,
+        })
+        .collect()
+}
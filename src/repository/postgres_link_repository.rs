@@ -0,0 +1,308 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{Postgres, QueryBuilder, Row};
+use url::Url;
+use uuid::Uuid;
+
+use crate::domain::{
+    Link, LinkQuery, LinkSort, LinkStatus, NEVER_EXPIRES_SORT_KEY, ShortCode, SortOrder, Utm,
+};
+use crate::error::{AppError, AppResult};
+
+use super::link_repository::{
+    LINK_COLUMNS, parse_passthrough, parse_redirect_type, parse_variants, passthrough_str,
+    target_host, variants_json,
+};
+use super::{LinkRepository, PgDbPool};
+
+/// PostgreSQL-backed link repository.
+#[derive(Clone)]
+pub struct PgLinkRepository {
+    pool: PgDbPool,
+}
+
+impl PgLinkRepository {
+    pub fn new(pool: PgDbPool) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_link(&self, row: PgRow) -> AppResult<Link> {
+        let short_code: String = row.get("short_code");
+        let target_url_str: String = row.get("target_url");
+        let target_url = Url::parse(&target_url_str)
+            .map_err(|e| AppError::Internal(format!("Invalid URL in database: {}", e)))?;
+        let prelaunch_url = row
+            .get::<Option<String>, _>("prelaunch_url")
+            .map(|s| Url::parse(&s))
+            .transpose()
+            .map_err(|e| AppError::Internal(format!("Invalid URL in database: {}", e)))?;
+
+        Ok(Link {
+            id: row.get("id"),
+            short_code: ShortCode::from_existing(short_code),
+            target_url,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            expires_at: row.get("expires_at"),
+            password_hash: row.get("password_hash"),
+            max_clicks: row.get::<Option<i64>, _>("max_clicks").map(|n| n as u32),
+            click_count: row.get::<i64, _>("click_count") as u32,
+            active_from: row.get("active_from"),
+            prelaunch_url,
+            variants: parse_variants(row.get("variants"))?,
+            redirect_type: parse_redirect_type(row.get("redirect_type"))?,
+            passthrough: parse_passthrough(row.get("passthrough"))?,
+            interstitial: row.get("interstitial"),
+        })
+    }
+}
+
+/// Parse a timestamp sort key from a cursor.
+fn parse_cursor_time(key: &str) -> AppResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(key)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| AppError::InvalidRequest("Invalid cursor".to_string()))
+}
+
+#[async_trait]
+impl LinkRepository for PgLinkRepository {
+    async fn create(&self, link: &Link) -> AppResult<Link> {
+        let short_code_str = link.short_code.as_str();
+        let utm = Utm::from_url(&link.target_url);
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO links (id, short_code, target_url, target_host, created_at, updated_at, expires_at, password_hash, max_clicks, click_count, active_from, prelaunch_url, variants, redirect_type, passthrough, utm_source, utm_medium, utm_campaign, utm_term, utm_content, interstitial)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            RETURNING {}
+            "#,
+            LINK_COLUMNS
+        ))
+        .bind(link.id)
+        .bind(short_code_str)
+        .bind(link.target_url.as_str())
+        .bind(target_host(&link.target_url))
+        .bind(link.created_at)
+        .bind(link.updated_at)
+        .bind(link.expires_at)
+        .bind(&link.password_hash)
+        .bind(link.max_clicks.map(i64::from))
+        .bind(i64::from(link.click_count))
+        .bind(link.active_from)
+        .bind(link.prelaunch_url.as_ref().map(|u| u.as_str()))
+        .bind(variants_json(&link.variants)?)
+        .bind(link.redirect_type.map(|r| r.as_str()))
+        .bind(passthrough_str(link.passthrough))
+        .bind(utm.source)
+        .bind(utm.medium)
+        .bind(utm.campaign)
+        .bind(utm.term)
+        .bind(utm.content)
+        .bind(link.interstitial)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e
+                && db_err.is_unique_violation()
+            {
+                return AppError::ShortCodeTaken(short_code_str.to_string());
+            }
+            AppError::Database(e)
+        })?;
+
+        self.row_to_link(row)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Link>> {
+        let row = sqlx::query(&format!("SELECT {} FROM links WHERE id = $1", LINK_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| self.row_to_link(row)).transpose()
+    }
+
+    async fn find_by_short_code(&self, short_code: &str) -> AppResult<Option<Link>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM links WHERE short_code = $1",
+            LINK_COLUMNS
+        ))
+        .bind(short_code)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_link(row)).transpose()
+    }
+
+    async fn list(&self, query: &LinkQuery, now: DateTime<Utc>) -> AppResult<Vec<Link>> {
+        let sort_expr = match query.sort {
+            LinkSort::CreatedAt => "created_at".to_string(),
+            LinkSort::ExpiresAt => format!(
+                "COALESCE(expires_at, '{}'::timestamptz)",
+                NEVER_EXPIRES_SORT_KEY
+            ),
+            // Byte order, matching the SQLite backend
+            LinkSort::ShortCode => "(short_code COLLATE \"C\")".to_string(),
+        };
+        let (cmp, direction) = match query.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new(format!("SELECT {} FROM links WHERE TRUE", LINK_COLUMNS));
+
+        match query.status {
+            Some(LinkStatus::Active) => {
+                builder
+                    .push(" AND (expires_at IS NULL OR expires_at > ")
+                    .push_bind(now)
+                    .push(") AND (max_clicks IS NULL OR click_count < max_clicks)")
+                    .push(" AND (active_from IS NULL OR active_from <= ")
+                    .push_bind(now)
+                    .push(")");
+            }
+            Some(LinkStatus::Expired) => {
+                builder
+                    .push(" AND ((expires_at IS NOT NULL AND expires_at <= ")
+                    .push_bind(now)
+                    .push(") OR click_count >= max_clicks)");
+            }
+            Some(LinkStatus::NeverExpires) => {
+                builder.push(" AND expires_at IS NULL");
+            }
+            Some(LinkStatus::Scheduled) => {
+                builder.push(" AND active_from > ").push_bind(now);
+            }
+            None => {}
+        }
+
+        if let Some(after) = query.created_after {
+            builder.push(" AND created_at >= ").push_bind(after);
+        }
+        if let Some(before) = query.created_before {
+            builder.push(" AND created_at < ").push_bind(before);
+        }
+
+        if let Some(domain) = &query.domain {
+            builder
+                .push(" AND (target_host = ")
+                .push_bind(domain.clone())
+                .push(" OR right(target_host, ")
+                .push_bind(domain.len() as i32 + 1)
+                .push(") = ")
+                .push_bind(format!(".{}", domain))
+                .push(")");
+        }
+
+        if let Some(prefix) = &query.short_code_prefix {
+            builder
+                .push(" AND starts_with(short_code, ")
+                .push_bind(prefix.clone())
+                .push(")");
+        }
+
+        if let Some(campaign) = &query.campaign {
+            builder
+                .push(" AND utm_campaign = ")
+                .push_bind(campaign.clone());
+        }
+
+        if let Some(cursor) = &query.cursor {
+            builder.push(format!(" AND ({}, id) {} (", sort_expr, cmp));
+            match query.sort {
+                LinkSort::CreatedAt | LinkSort::ExpiresAt => {
+                    builder.push_bind(parse_cursor_time(&cursor.key)?);
+                }
+                LinkSort::ShortCode => {
+                    builder.push_bind(cursor.key.clone()).push(" COLLATE \"C\"");
+                }
+            }
+            builder.push(", ").push_bind(cursor.id).push(")");
+        }
+
+        builder
+            .push(format!(
+                " ORDER BY {} {}, id {} LIMIT ",
+                sort_expr, direction, direction
+            ))
+            .push_bind(query.limit as i64);
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        rows.into_iter().map(|row| self.row_to_link(row)).collect()
+    }
+
+    async fn update(&self, link: &Link) -> AppResult<bool> {
+        let utm = Utm::from_url(&link.target_url);
+        let result = sqlx::query(
+            r#"
+            UPDATE links
+            SET target_url = $1, target_host = $2, updated_at = $3, expires_at = $4, variants = $5,
+                redirect_type = $6, passthrough = $7, utm_source = $8, utm_medium = $9,
+                utm_campaign = $10, utm_term = $11, utm_content = $12, interstitial = $13
+            WHERE id = $14
+            "#,
+        )
+        .bind(link.target_url.as_str())
+        .bind(target_host(&link.target_url))
+        .bind(link.updated_at)
+        .bind(link.expires_at)
+        .bind(variants_json(&link.variants)?)
+        .bind(link.redirect_type.map(|r| r.as_str()))
+        .bind(passthrough_str(link.passthrough))
+        .bind(utm.source)
+        .bind(utm.medium)
+        .bind(utm.campaign)
+        .bind(utm.term)
+        .bind(utm.content)
+        .bind(link.interstitial)
+        .bind(link.id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM links WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn consume_click(&self, id: Uuid, now: DateTime<Utc>) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE links
+            SET click_count = click_count + 1
+            WHERE id = $1 AND click_count < max_clicks
+              AND (expires_at IS NULL OR expires_at > $2)
+            "#,
+        )
+        .bind(id)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired(&self) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM links
+            WHERE (expires_at IS NOT NULL AND expires_at < $1)
+               OR click_count >= max_clicks
+            "#,
+        )
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
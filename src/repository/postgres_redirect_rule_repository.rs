@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::domain::RedirectRule;
+use crate::error::AppResult;
+
+use super::redirect_rule_repository::{conditions_json, stored_rule};
+use super::{PgDbPool, RedirectRuleRepository};
+
+/// PostgreSQL-backed redirect rule repository.
+#[derive(Clone)]
+pub struct PgRedirectRuleRepository {
+    pool: PgDbPool,
+}
+
+impl PgRedirectRuleRepository {
+    pub fn new(pool: PgDbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RedirectRuleRepository for PgRedirectRuleRepository {
+    async fn list(&self, link_id: Uuid) -> AppResult<Vec<RedirectRule>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT conditions::text AS conditions, target_url
+            FROM redirect_rules
+            WHERE link_id = $1
+            ORDER BY position ASC
+            "#,
+        )
+        .bind(link_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| stored_rule(row.get("conditions"), row.get("target_url")))
+            .collect()
+    }
+
+    async fn replace(&self, link_id: Uuid, rules: &[RedirectRule]) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM redirect_rules WHERE link_id = $1")
+            .bind(link_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, rule) in rules.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO redirect_rules (link_id, position, conditions, target_url)
+                VALUES ($1, $2, $3::jsonb, $4)
+                "#,
+            )
+            .bind(link_id)
+            .bind(position as i32)
+            .bind(conditions_json(rule)?)
+            .bind(rule.url.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::domain::{RedirectRule, RuleConditions};
+use crate::error::{AppError, AppResult};
+
+use super::DbPool;
+
+/// Storage operations for per-link redirect rules, implemented by each backend.
+#[async_trait]
+pub trait RedirectRuleRepository: Send + Sync {
+    /// List the rules of a link in evaluation order.
+    async fn list(&self, link_id: Uuid) -> AppResult<Vec<RedirectRule>>;
+
+    /// Replace all the rules of a link in a single transaction.
+    async fn replace(&self, link_id: Uuid, rules: &[RedirectRule]) -> AppResult<()>;
+}
+
+/// Rebuild a rule from its stored JSON conditions and target URL.
+pub(super) fn stored_rule(conditions: &str, target_url: &str) -> AppResult<RedirectRule> {
+    let conditions: RuleConditions = serde_json::from_str(conditions)
+        .map_err(|e| AppError::Internal(format!("Invalid rule conditions: {}", e)))?;
+    let url = target_url
+        .parse()
+        .map_err(|e| AppError::Internal(format!("Invalid rule URL: {}", e)))?;
+    Ok(RedirectRule { conditions, url })
+}
+
+/// Serialize rule conditions for storage.
+pub(super) fn conditions_json(rule: &RedirectRule) -> AppResult<String> {
+    serde_json::to_string(&rule.conditions)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize rule conditions: {}", e)))
+}
+
+/// SQLite-backed redirect rule repository.
+#[derive(Clone)]
+pub struct SqliteRedirectRuleRepository {
+    pool: DbPool,
+}
+
+impl SqliteRedirectRuleRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RedirectRuleRepository for SqliteRedirectRuleRepository {
+    async fn list(&self, link_id: Uuid) -> AppResult<Vec<RedirectRule>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT conditions, target_url
+            FROM redirect_rules
+            WHERE link_id = ?
+            ORDER BY position ASC
+            "#,
+        )
+        .bind(link_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| stored_rule(row.get("conditions"), row.get("target_url")))
+            .collect()
+    }
+
+    async fn replace(&self, link_id: Uuid, rules: &[RedirectRule]) -> AppResult<()> {
+        let link_id_str = link_id.to_string();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM redirect_rules WHERE link_id = ?")
+            .bind(&link_id_str)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, rule) in rules.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO redirect_rules (link_id, position, conditions, target_url)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(&link_id_str)
+            .bind(position as i64)
+            .bind(conditions_json(rule)?)
+            .bind(rule.url.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
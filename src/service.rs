@@ -1,7 +1,16 @@
+#[path = "service/analytics_service.rs"]
+mod analytics_service;
+#[path = "service/api_key_service.rs"]
+mod api_key_service;
 #[path = "service/link_service.rs"]
 mod link_service;
 #[path = "service/qr_service.rs"]
 mod qr_service;
+#[path = "service/redirect_rule_service.rs"]
+mod redirect_rule_service;
 
-pub use link_service::LinkService;
+pub use analytics_service::AnalyticsService;
+pub use api_key_service::ApiKeyService;
+pub use link_service::{LinkService, LinkUpdate, NewLink};
 pub use qr_service::QrService;
+pub use redirect_rule_service::{RedirectRuleService, RuleRouting};
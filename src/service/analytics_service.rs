@@ -0,0 +1,265 @@
+use chrono::Utc;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+use crate::domain::{CampaignStats, Click, LinkStats};
+use crate::error::AppResult;
+use crate::repository::ClickRepository;
+
+/// Maximum number of click events waiting to be written.
+const BUFFER_CAPACITY: usize = 10_000;
+
+/// Number of buffered clicks that triggers an immediate write.
+const BATCH_SIZE: usize = 500;
+
+/// Maximum time a click stays buffered before being written.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Messages sent to the background click writer.
+enum Command {
+    Record(Click),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Service for recording redirects and reading per-link statistics.
+/// Clicks are buffered in memory and written in batches by a background task,
+/// so recording never waits on the database.
+#[derive(Clone)]
+pub struct AnalyticsService {
+    repo: Arc<dyn ClickRepository>,
+    sender: mpsc::Sender<Command>,
+    ip_hash_salt: Arc<str>,
+}
+
+impl AnalyticsService {
+    /// Create the service and spawn its background writer.
+    /// Must be called from within a Tokio runtime.
+    pub fn new(repo: Arc<dyn ClickRepository>, ip_hash_salt: String) -> Self {
+        let (sender, receiver) = mpsc::channel(BUFFER_CAPACITY);
+        tokio::spawn(run_writer(repo.clone(), receiver));
+
+        Self {
+            repo,
+            sender,
+            ip_hash_salt: ip_hash_salt.into(),
+        }
+    }
+
+    /// Record a click, and the variant served for split links, without waiting
+    /// for it to be persisted.
+    /// If the buffer is full the click is dropped rather than slowing down the redirect.
+    pub fn record(
+        &self,
+        link_id: Uuid,
+        ip: IpAddr,
+        referrer: Option<&str>,
+        user_agent: Option<&str>,
+        variant: Option<&str>,
+    ) {
+        let click = Click {
+            variant: variant.map(str::to_string),
+            ..Click::new(
+                link_id,
+                Utc::now(),
+                ip,
+                &self.ip_hash_salt,
+                referrer,
+                user_agent,
+            )
+        };
+
+        if self.sender.try_send(Command::Record(click)).is_err() {
+            tracing::warn!("Click buffer full, dropping click for link {}", link_id);
+        }
+    }
+
+    /// Write all buffered clicks and wait for completion.
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.sender.send(Command::Flush(done_tx)).await.is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+
+    /// Get click statistics for a link.
+    pub async fn stats(&self, link_id: Uuid) -> AppResult<LinkStats> {
+        self.repo.stats(link_id).await
+    }
+
+    /// Get click statistics across all links of a UTM campaign.
+    pub async fn campaign_stats(&self, campaign: &str) -> AppResult<CampaignStats> {
+        self.repo.campaign_stats(campaign).await
+    }
+}
+
+/// Background task draining the click channel into the database.
+async fn run_writer(repo: Arc<dyn ClickRepository>, mut receiver: mpsc::Receiver<Command>) {
+    let mut buffer: Vec<Click> = Vec::with_capacity(BATCH_SIZE);
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(Command::Record(click)) => {
+                    buffer.push(click);
+                    if buffer.len() >= BATCH_SIZE {
+                        write_batch(&repo, &mut buffer).await;
+                    }
+                }
+                Some(Command::Flush(done)) => {
+                    write_batch(&repo, &mut buffer).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    write_batch(&repo, &mut buffer).await;
+                    break;
+                }
+            },
+            _ = ticker.tick() => write_batch(&repo, &mut buffer).await,
+        }
+    }
+}
+
+async fn write_batch(repo: &Arc<dyn ClickRepository>, buffer: &mut Vec<Click>) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    if let Err(e) = repo.insert_batch(buffer).await {
+        tracing::error!("Failed to write {} click(s): {}", buffer.len(), e);
+    }
+    buffer.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Ttl, Utm};
+    use crate::repository::{SqliteClickRepository, SqliteLinkRepository, init_db};
+    use crate::service::{LinkService, NewLink};
+
+    #[tokio::test]
+    async fn test_stats_count_clicks_and_unique_visitors() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let link_service = LinkService::new(
+            Arc::new(SqliteLinkRepository::new(pool.clone())),
+            "http://test.local".to_string(),
+        );
+        let analytics = AnalyticsService::new(
+            Arc::new(SqliteClickRepository::new(pool)),
+            "salt".to_string(),
+        );
+
+        let link = link_service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let ip1: IpAddr = "10.0.0.1".parse().unwrap();
+        let ip2: IpAddr = "10.0.0.2".parse().unwrap();
+        analytics.record(
+            link.id,
+            ip1,
+            Some("https://news.example"),
+            Some("curl/8"),
+            None,
+        );
+        analytics.record(link.id, ip1, None, None, None);
+        analytics.record(link.id, ip2, None, None, None);
+        analytics.flush().await;
+
+        let stats = analytics.stats(link.id).await.unwrap();
+        assert_eq!(stats.total_clicks, 3);
+        assert_eq!(stats.unique_visitors, 2);
+        assert_eq!(stats.daily.len(), 1);
+        assert_eq!(stats.daily[0].date, Utc::now().date_naive());
+        assert_eq!(stats.daily[0].clicks, 3);
+        assert_eq!(stats.daily[0].unique_visitors, 2);
+    }
+
+    #[tokio::test]
+    async fn test_clicks_for_deleted_link_are_skipped() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let link_service = LinkService::new(
+            Arc::new(SqliteLinkRepository::new(pool.clone())),
+            "http://test.local".to_string(),
+        );
+        let analytics = AnalyticsService::new(
+            Arc::new(SqliteClickRepository::new(pool)),
+            "salt".to_string(),
+        );
+
+        let link = link_service
+            .create_link("https://example.com", NewLink::default())
+            .await
+            .unwrap();
+
+        analytics.record(link.id, "10.0.0.1".parse().unwrap(), None, None, None);
+        link_service.delete_link(link.id).await.unwrap();
+        analytics.flush().await;
+
+        let stats = analytics.stats(link.id).await.unwrap();
+        assert_eq!(stats.total_clicks, 0);
+        assert!(stats.daily.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_campaign_stats_span_tagged_links() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let link_service = LinkService::new(
+            Arc::new(SqliteLinkRepository::new(pool.clone())),
+            "http://test.local".to_string(),
+        );
+        let analytics = AnalyticsService::new(
+            Arc::new(SqliteClickRepository::new(pool)),
+            "salt".to_string(),
+        );
+
+        let tagged = |campaign: &str| NewLink {
+            utm: Utm {
+                source: Some("flyer".to_string()),
+                campaign: Some(campaign.to_string()),
+                ..Utm::default()
+            },
+            ..NewLink::default()
+        };
+        let poster = link_service
+            .create_link("https://example.com/a", tagged("spring"))
+            .await
+            .unwrap();
+        let flyer = link_service
+            .create_link("https://example.com/b", tagged("spring"))
+            .await
+            .unwrap();
+        let other = link_service
+            .create_link("https://example.com/c", tagged("autumn"))
+            .await
+            .unwrap();
+
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        analytics.record(poster.id, ip, None, None, None);
+        analytics.record(flyer.id, ip, None, None, None);
+        analytics.record(flyer.id, "10.0.0.2".parse().unwrap(), None, None, None);
+        analytics.record(other.id, ip, None, None, None);
+        analytics.flush().await;
+
+        let stats = analytics.campaign_stats("spring").await.unwrap();
+        assert_eq!(stats.links, 2);
+        assert_eq!(stats.total_clicks, 3);
+        assert_eq!(stats.unique_visitors, 2);
+        assert_eq!(stats.daily.len(), 1);
+
+        let unknown = analytics.campaign_stats("winter").await.unwrap();
+        assert_eq!(unknown.links, 0);
+        assert_eq!(unknown.total_clicks, 0);
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::{ApiKey, ApiKeyResponse, Scope};
+use crate::error::{AppError, AppResult};
+use crate::repository::ApiKeyRepository;
+
+/// Maximum length of an API key name.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Service for issuing, revoking and checking API keys.
+#[derive(Clone)]
+pub struct ApiKeyService {
+    repo: Arc<dyn ApiKeyRepository>,
+}
+
+impl ApiKeyService {
+    pub fn new(repo: Arc<dyn ApiKeyRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Issue a new API key.
+    /// Returns the key metadata and the plaintext key, which is not stored and cannot be shown again.
+    pub async fn create_key(
+        &self,
+        name: &str,
+        scopes: Vec<Scope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<(ApiKeyResponse, String)> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(AppError::InvalidRequest(format!(
+                "Key name must be between 1 and {} characters",
+                MAX_NAME_LENGTH
+            )));
+        }
+
+        if scopes.is_empty() {
+            return Err(AppError::InvalidRequest(
+                "At least one scope is required".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        if expires_at.is_some_and(|exp| exp <= now) {
+            return Err(AppError::InvalidRequest(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+
+        let mut scopes = scopes;
+        scopes.sort_by_key(|s| s.as_str());
+        scopes.dedup();
+
+        let (key, prefix) = ApiKey::generate_secret();
+        let api_key = ApiKey {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            prefix,
+            key_hash: ApiKey::hash(&key),
+            scopes,
+            created_at: now,
+            expires_at,
+            revoked_at: None,
+        };
+
+        self.repo.create(&api_key).await?;
+        Ok((ApiKeyResponse::from(&api_key), key))
+    }
+
+    /// List all API keys, including revoked and expired ones.
+    pub async fn list_keys(&self) -> AppResult<Vec<ApiKeyResponse>> {
+        let keys = self.repo.list_all().await?;
+        Ok(keys.iter().map(ApiKeyResponse::from).collect())
+    }
+
+    /// Revoke an API key by ID.
+    pub async fn revoke_key(&self, key_id: Uuid) -> AppResult<()> {
+        let revoked = self.repo.revoke(key_id, Utc::now()).await?;
+        if !revoked {
+            return Err(AppError::ApiKeyNotFound);
+        }
+        Ok(())
+    }
+
+    /// Resolve a plaintext key to an active API key.
+    /// Unknown, malformed, expired and revoked keys are all rejected the same way.
+    pub async fn authenticate(&self, key: &str) -> AppResult<ApiKey> {
+        let prefix = ApiKey::prefix_of(key).ok_or(AppError::InvalidApiKey)?;
+
+        let api_key = self
+            .repo
+            .find_by_prefix(prefix)
+            .await?
+            .ok_or(AppError::InvalidApiKey)?;
+
+        if !api_key.verify(key) || !api_key.is_active(Utc::now()) {
+            return Err(AppError::InvalidApiKey);
+        }
+
+        Ok(api_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{SqliteApiKeyRepository, init_db};
+    use chrono::Duration;
+
+    async fn create_service() -> ApiKeyService {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        ApiKeyService::new(Arc::new(SqliteApiKeyRepository::new(pool)))
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate_key() {
+        let service = create_service().await;
+
+        let (created, key) = service
+            .create_key("ci", vec![Scope::LinksRead, Scope::LinksCreate], None)
+            .await
+            .unwrap();
+
+        let api_key = service.authenticate(&key).await.unwrap();
+        assert_eq!(api_key.id, created.id);
+        assert!(api_key.has_scope(Scope::LinksRead));
+        assert!(api_key.has_scope(Scope::LinksCreate));
+        assert!(!api_key.has_scope(Scope::LinksDelete));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_wrong_secret() {
+        let service = create_service().await;
+        let (_, key) = service
+            .create_key("ci", vec![Scope::LinksRead], None)
+            .await
+            .unwrap();
+
+        // Same prefix, different secret
+        let last = if key.ends_with('A') { 'B' } else { 'A' };
+        let tampered = format!("{}{}", &key[..key.len() - 1], last);
+        assert!(matches!(
+            service.authenticate(&tampered).await,
+            Err(AppError::InvalidApiKey)
+        ));
+        assert!(matches!(
+            service.authenticate("garbage").await,
+            Err(AppError::InvalidApiKey)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_is_rejected() {
+        let service = create_service().await;
+        let (created, key) = service
+            .create_key("ci", vec![Scope::LinksRead], None)
+            .await
+            .unwrap();
+
+        service.revoke_key(created.id).await.unwrap();
+
+        assert!(matches!(
+            service.authenticate(&key).await,
+            Err(AppError::InvalidApiKey)
+        ));
+        assert!(matches!(
+            service.revoke_key(created.id).await,
+            Err(AppError::ApiKeyNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_key_validation() {
+        let service = create_service().await;
+
+        assert!(
+            service
+                .create_key("  ", vec![Scope::LinksRead], None)
+                .await
+                .is_err()
+        );
+        assert!(service.create_key("ci", vec![], None).await.is_err());
+
+        let past = Utc::now() - Duration::hours(1);
+        assert!(
+            service
+                .create_key("ci", vec![Scope::LinksRead], Some(past))
+                .await
+                .is_err()
+        );
+    }
+}
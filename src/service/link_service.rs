@@ -1,47 +1,167 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
 use url::Url;
 use uuid::Uuid;
 
-use crate::domain::{Link, LinkResponse, ShortCode, Ttl};
+use crate::domain::{
+    Link, LinkCursor, LinkPage, LinkQuery, LinkResponse, MAX_PAGE_SIZE, Passthrough, RedirectType,
+    ShortCode, Ttl, TtlBounds, Utm, Variant, validate_variants,
+};
 use crate::error::{AppError, AppResult};
+use crate::metrics;
 use crate::repository::LinkRepository;
 
 /// Maximum number of retries when generating a short code.
 const MAX_RETRIES: usize = 5;
 
+/// Options for a new link; unset fields use the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct NewLink {
+    /// Expiry, counted from creation; defaults to the maximum TTL, if any
+    pub ttl: Option<Ttl>,
+    /// Custom short code (e.g., "spring-sale") instead of a random one
+    pub alias: Option<String>,
+    /// Password required to follow the link (stored hashed)
+    pub password: Option<String>,
+    /// Number of redirects after which the link is used up (1 for single-use links)
+    pub max_clicks: Option<u32>,
+    /// Time from which the link redirects to its target
+    pub active_from: Option<DateTime<Utc>>,
+    /// Where to send visitors before `active_from`, instead of a holding page
+    pub prelaunch_url: Option<String>,
+    /// Weighted destinations splitting the traffic instead of the target URL
+    pub variants: Vec<Variant>,
+    /// Redirect status for the link instead of the server default
+    pub redirect_type: Option<RedirectType>,
+    /// Forwarding of extra path segments and the query to the destination
+    pub passthrough: Passthrough,
+    /// Campaign tracking parameters to add to the target URL
+    pub utm: Utm,
+    /// Show visitors a preview page with a continue button instead of redirecting
+    pub interstitial: bool,
+}
+
+/// Changes to an existing link; unset fields are left unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct LinkUpdate {
+    /// New target URL
+    pub url: Option<String>,
+    /// New expiry, counted from now; `Ttl::Never` removes the expiry
+    pub ttl: Option<Ttl>,
+    /// New redirect status for the link
+    pub redirect_type: Option<RedirectType>,
+    /// New passthrough mode for the link
+    pub passthrough: Option<Passthrough>,
+    /// Turn the preview page shown before redirecting on or off
+    pub interstitial: Option<bool>,
+    /// UTM parameters to set on the target URL, replacing the same ones it has
+    pub utm: Option<Utm>,
+}
+
 /// Service for link-related business operations.
 #[derive(Clone)]
 pub struct LinkService {
-    repo: LinkRepository,
+    repo: Arc<dyn LinkRepository>,
     base_url: String,
+    ttl_bounds: TtlBounds,
 }
 
 impl LinkService {
-    pub fn new(repo: LinkRepository, base_url: String) -> Self {
-        Self { repo, base_url }
+    pub fn new(repo: Arc<dyn LinkRepository>, base_url: String) -> Self {
+        Self {
+            repo,
+            base_url,
+            ttl_bounds: TtlBounds::default(),
+        }
+    }
+
+    /// Limit the lifetime of created and updated links.
+    pub fn with_ttl_bounds(mut self, ttl_bounds: TtlBounds) -> Self {
+        self.ttl_bounds = ttl_bounds;
+        self
     }
 
     /// Create a new short link.
-    pub async fn create_link(&self, target_url: &str, ttl: Option<Ttl>) -> AppResult<LinkResponse> {
+    pub async fn create_link(&self, target_url: &str, new: NewLink) -> AppResult<LinkResponse> {
         // Validate URL
         let url = Url::parse(target_url)
             .map_err(|e| AppError::InvalidUrl(format!("{}: {}", e, target_url)))?;
 
+        new.utm.validate().map_err(AppError::InvalidRequest)?;
+        let url = new.utm.apply(&url);
+
+        let password_hash = match new.password.as_deref() {
+            Some("") => {
+                return Err(AppError::InvalidRequest(
+                    "Password must not be empty".to_string(),
+                ));
+            }
+            Some(password) => Some(Link::hash_password(password).map_err(AppError::Internal)?),
+            None => None,
+        };
+
+        if new.max_clicks == Some(0) {
+            return Err(AppError::InvalidRequest(
+                "max_clicks must be at least 1".to_string(),
+            ));
+        }
+
         let now = Utc::now();
-        let expires_at = ttl.and_then(|t| t.expires_at(now));
+        let expires_at = self
+            .ttl_bounds
+            .expiry(new.ttl, now)
+            .map_err(AppError::InvalidTtl)?;
+
+        check_schedule(new.active_from, expires_at)?;
+
+        let prelaunch_url = match new.prelaunch_url.as_deref() {
+            Some(_) if new.active_from.is_none() => {
+                return Err(AppError::InvalidRequest(
+                    "prelaunch_url requires active_from".to_string(),
+                ));
+            }
+            Some(prelaunch_url) => Some(
+                Url::parse(prelaunch_url)
+                    .map_err(|e| AppError::InvalidUrl(format!("{}: {}", e, prelaunch_url)))?,
+            ),
+            None => None,
+        };
+
+        validate_variants(&new.variants).map_err(AppError::InvalidRequest)?;
+
+        let mut link = Link {
+            expires_at,
+            password_hash,
+            max_clicks: new.max_clicks,
+            active_from: new.active_from,
+            prelaunch_url,
+            variants: new.variants,
+            redirect_type: new.redirect_type,
+            passthrough: new.passthrough,
+            interstitial: new.interstitial,
+            ..Link::new(ShortCode::generate(), url, now)
+        };
+        check_passthrough(&link)?;
+
+        // Custom alias: no retry, a collision is reported to the caller
+        if let Some(alias) = new.alias.as_deref() {
+            link.short_code = ShortCode::from_alias(alias).map_err(AppError::InvalidAlias)?;
+            let link = self.repo.create(&link).await?;
+            metrics::LINKS_CREATED.inc();
+            return Ok(LinkResponse::from_link(&link, &self.base_url));
+        }
 
         // Try to create with collision retry
         for _ in 0..MAX_RETRIES {
-            let id = Uuid::new_v4();
-            let short_code = ShortCode::generate();
-
-            match self
-                .repo
-                .create(id, &short_code, &url, now, expires_at)
-                .await
-            {
-                Ok(link) => return Ok(LinkResponse::from_link(&link, &self.base_url)),
-                Err(AppError::ShortCodeExhausted) => continue,
+            match self.repo.create(&link).await {
+                Ok(link) => {
+                    metrics::LINKS_CREATED.inc();
+                    return Ok(LinkResponse::from_link(&link, &self.base_url));
+                }
+                Err(AppError::ShortCodeTaken(_)) => {
+                    link.id = Uuid::new_v4();
+                    link.short_code = ShortCode::generate();
+                }
                 Err(e) => return Err(e),
             }
         }
@@ -49,8 +169,54 @@ impl LinkService {
         Err(AppError::ShortCodeExhausted)
     }
 
-    /// Resolve a short code to a link for redirection.
+    /// Resolve a short code to a link for redirection, counting the redirect
+    /// against the link's click limit.
+    /// Protected links are only counted once unlocked, and interstitial links once
+    /// continued, by [`Self::check_password`].
     pub async fn resolve(&self, short_code: &str) -> AppResult<Link> {
+        self.resolve_link(short_code, false).await
+    }
+
+    /// Resolve a short code followed by extra path segments, like [`Self::resolve`].
+    /// Only links with passthrough enabled are found this way.
+    pub async fn resolve_with_suffix(&self, short_code: &str) -> AppResult<Link> {
+        self.resolve_link(short_code, true).await
+    }
+
+    async fn resolve_link(&self, short_code: &str, with_suffix: bool) -> AppResult<Link> {
+        let link = self
+            .repo
+            .find_by_short_code(short_code)
+            .await?
+            .filter(|link| !with_suffix || !link.passthrough.is_off())
+            .ok_or(AppError::LinkNotFound)?;
+
+        let now = Utc::now();
+        if link.is_expired(now) {
+            return Err(AppError::LinkExpired);
+        }
+        if let Some(active_from) = link.active_from
+            && now < active_from
+        {
+            return Err(AppError::LinkNotActive {
+                active_from,
+                prelaunch_url: link.prelaunch_url,
+            });
+        }
+        if link.is_exhausted() {
+            return Err(AppError::LinkExhausted);
+        }
+
+        if link.is_protected() || link.interstitial {
+            Ok(link)
+        } else {
+            self.consume_click(link).await
+        }
+    }
+
+    /// Look up a link by short code for its preview page or QR code, without
+    /// counting a redirect. Expired and used-up links are reported as such.
+    pub async fn get_by_short_code(&self, short_code: &str) -> AppResult<LinkResponse> {
         let link = self
             .repo
             .find_by_short_code(short_code)
@@ -60,17 +226,201 @@ impl LinkService {
         if link.is_expired(Utc::now()) {
             return Err(AppError::LinkExpired);
         }
+        if link.is_exhausted() {
+            return Err(AppError::LinkExhausted);
+        }
+
+        Ok(self.to_response(&link))
+    }
+
+    /// API representation of a link, with its short URL.
+    pub fn to_response(&self, link: &Link) -> LinkResponse {
+        LinkResponse::from_link(link, &self.base_url)
+    }
+
+    /// Count a redirect of a click-limited link.
+    /// The check and increment happen in one database update, so concurrent
+    /// redirects cannot go over the limit.
+    async fn consume_click(&self, mut link: Link) -> AppResult<Link> {
+        if link.max_clicks.is_none() {
+            return Ok(link);
+        }
 
+        if !self.repo.consume_click(link.id, Utc::now()).await? {
+            // Used up (or expired) by a concurrent redirect since it was read
+            return Err(AppError::LinkExhausted);
+        }
+        link.click_count += 1;
         Ok(link)
     }
 
-    /// List all links (no authentication required).
-    pub async fn list_all(&self) -> AppResult<Vec<LinkResponse>> {
-        let links = self.repo.list_all().await?;
-        Ok(links
-            .iter()
-            .map(|l| LinkResponse::from_link(l, &self.base_url))
-            .collect())
+    /// Check the password of a protected link, returning the link if it matches
+    /// and counting the redirect against its click limit.
+    /// Unprotected links accept any password; plain ones were already counted
+    /// when resolved, so only interstitial ones are counted here.
+    /// Hashing is CPU-heavy, so it runs on the blocking thread pool.
+    pub async fn check_password(&self, link: Link, password: String) -> AppResult<Link> {
+        let link = tokio::task::spawn_blocking(move || {
+            if link.verify_password(&password) {
+                Ok(link)
+            } else {
+                Err(AppError::InvalidPassword)
+            }
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Password check failed: {}", e)))??;
+
+        if !link.is_protected() && !link.interstitial {
+            return Ok(link);
+        }
+        self.consume_click(link).await
+    }
+
+    /// Get a single link by ID.
+    pub async fn get_link(&self, link_id: Uuid) -> AppResult<LinkResponse> {
+        let link = self
+            .repo
+            .find_by_id(link_id)
+            .await?
+            .ok_or(AppError::LinkNotFound)?;
+        Ok(LinkResponse::from_link(&link, &self.base_url))
+    }
+
+    /// List one page of links matching the query's filters.
+    pub async fn list_links(&self, mut query: LinkQuery) -> AppResult<LinkPage> {
+        if query.limit == 0 || query.limit > MAX_PAGE_SIZE {
+            return Err(AppError::InvalidRequest(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+
+        if let Some(cursor) = &query.cursor
+            && cursor.sort != query.sort
+        {
+            return Err(AppError::InvalidRequest(
+                "Cursor was issued for a different sort".to_string(),
+            ));
+        }
+
+        if let (Some(after), Some(before)) = (query.created_after, query.created_before)
+            && after >= before
+        {
+            return Err(AppError::InvalidRequest(
+                "created_after must be before created_before".to_string(),
+            ));
+        }
+
+        query.domain = query
+            .domain
+            .map(|d| d.trim().trim_start_matches('.').to_lowercase())
+            .filter(|d| !d.is_empty());
+        query.short_code_prefix = query.short_code_prefix.filter(|p| !p.is_empty());
+        query.campaign = query.campaign.filter(|c| !c.is_empty());
+
+        // Fetch one extra link to find out whether there is a next page
+        let limit = query.limit as usize;
+        query.limit += 1;
+        let mut links = self.repo.list(&query, Utc::now()).await?;
+
+        let next_cursor = if links.len() > limit {
+            links.truncate(limit);
+            links
+                .last()
+                .map(|last| LinkCursor::after(last, query.sort).encode())
+        } else {
+            None
+        };
+
+        Ok(LinkPage {
+            links: links
+                .iter()
+                .map(|l| LinkResponse::from_link(l, &self.base_url))
+                .collect(),
+            next_cursor,
+        })
+    }
+
+    /// Update a link's target URL, expiry, redirect type, passthrough and/or interstitial.
+    /// A new `ttl` is applied from now, within the TTL bounds; `Ttl::Never` removes the expiry.
+    pub async fn update_link(&self, link_id: Uuid, update: LinkUpdate) -> AppResult<LinkResponse> {
+        let mut link = self
+            .repo
+            .find_by_id(link_id)
+            .await?
+            .ok_or(AppError::LinkNotFound)?;
+
+        let now = Utc::now();
+
+        if let Some(utm) = &update.utm {
+            utm.validate().map_err(AppError::InvalidRequest)?;
+        }
+
+        if let Some(target_url) = update.url.as_deref() {
+            let url = Url::parse(target_url)
+                .map_err(|e| AppError::InvalidUrl(format!("{}: {}", e, target_url)))?;
+            // Retargeting keeps the link in its campaign: parameters the new URL
+            // does not set are carried over from the previous target
+            let carried = Utm::from_url(&url).or(Utm::from_url(&link.target_url));
+            link.target_url = carried.apply(&url);
+        }
+        if let Some(utm) = &update.utm {
+            link.target_url = utm.apply(&link.target_url);
+        }
+
+        if let Some(ttl) = update.ttl {
+            link.expires_at = self
+                .ttl_bounds
+                .expiry(Some(ttl), now)
+                .map_err(AppError::InvalidTtl)?;
+            check_schedule(link.active_from, link.expires_at)?;
+        }
+
+        if update.redirect_type.is_some() {
+            link.redirect_type = update.redirect_type;
+        }
+
+        if let Some(passthrough) = update.passthrough {
+            link.passthrough = passthrough;
+        }
+
+        if let Some(interstitial) = update.interstitial {
+            link.interstitial = interstitial;
+        }
+
+        check_passthrough(&link)?;
+
+        link.updated_at = now;
+
+        if !self.repo.update(&link).await? {
+            return Err(AppError::LinkNotFound);
+        }
+
+        Ok(LinkResponse::from_link(&link, &self.base_url))
+    }
+
+    /// Replace the A/B split destinations of a link. An empty list ends the split,
+    /// sending all traffic to the target URL again.
+    pub async fn set_variants(
+        &self,
+        link_id: Uuid,
+        variants: Vec<Variant>,
+    ) -> AppResult<LinkResponse> {
+        validate_variants(&variants).map_err(AppError::InvalidRequest)?;
+
+        let mut link = self
+            .repo
+            .find_by_id(link_id)
+            .await?
+            .ok_or(AppError::LinkNotFound)?;
+        link.variants = variants;
+        link.updated_at = Utc::now();
+
+        if !self.repo.update(&link).await? {
+            return Err(AppError::LinkNotFound);
+        }
+
+        Ok(LinkResponse::from_link(&link, &self.base_url))
     }
 
     /// Delete a link by ID.
@@ -82,56 +432,92 @@ impl LinkService {
         Ok(())
     }
 
-    /// Clean up expired links (for periodic job).
+    /// Clean up expired and used-up links (for periodic job).
     pub async fn cleanup_expired(&self) -> AppResult<u64> {
-        self.repo.delete_expired().await
+        let deleted = self.repo.delete_expired().await?;
+        metrics::CLEANUP_DELETED.inc_by(deleted);
+        Ok(deleted)
     }
 }
 
+/// A scheduled link must activate before it expires, or it would never redirect.
+fn check_schedule(
+    active_from: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+) -> AppResult<()> {
+    if let (Some(active_from), Some(expires_at)) = (active_from, expires_at)
+        && active_from >= expires_at
+    {
+        return Err(AppError::InvalidRequest(
+            "active_from must be before the expiry".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Passthrough is not available on protected or interstitial links: the unlock form
+/// and the continue button post back to the bare short code, which would drop the
+/// forwarded path and query.
+fn check_passthrough(link: &Link) -> AppResult<()> {
+    if !link.passthrough.is_off() && (link.is_protected() || link.interstitial) {
+        return Err(AppError::InvalidRequest(
+            "passthrough is not available on password-protected or interstitial links".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repository::init_db;
+    use crate::domain::{LinkSort, LinkStatus, SortOrder};
+    use crate::repository::{SqliteLinkRepository, init_db};
     use chrono::Duration;
 
     #[tokio::test]
     async fn test_cleanup_expired_links() {
         // Setup in-memory database
         let pool = init_db("sqlite::memory:").await.unwrap();
-        let repo = LinkRepository::new(pool);
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
         let service = LinkService::new(repo.clone(), "http://test.local".to_string());
 
         let now = Utc::now();
 
         // Create link that expired 1 hour ago (manually via repo)
-        let expired_id = Uuid::new_v4();
         let expired_code = ShortCode::generate();
         let expired_url = Url::parse("https://expired.com").unwrap();
         let expired_at = now - Duration::hours(1);
-        repo.create(
-            expired_id,
-            &expired_code,
-            &expired_url,
-            now,
-            Some(expired_at),
-        )
+        repo.create(&Link {
+            expires_at: Some(expired_at),
+            ..Link::new(expired_code.clone(), expired_url, now)
+        })
         .await
         .unwrap();
 
         // Create link that expires in 1 week (via service)
         let valid_link = service
-            .create_link("https://valid.com", Some(Ttl::OneWeek))
+            .create_link(
+                "https://valid.com",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    ..NewLink::default()
+                },
+            )
             .await
             .unwrap();
 
         // Create link with no expiration (via service)
         let permanent_link = service
-            .create_link("https://permanent.com", None)
+            .create_link("https://permanent.com", NewLink::default())
             .await
             .unwrap();
 
         // Verify all links exist
-        let all_links_before = service.list_all().await.unwrap();
+        let all_links_before = service
+            .list_links(LinkQuery::default())
+            .await
+            .unwrap()
+            .links;
         assert_eq!(all_links_before.len(), 3);
 
         // Run cleanup
@@ -141,7 +527,11 @@ mod tests {
         assert_eq!(deleted_count, 1);
 
         // Verify only 2 links remain
-        let all_links_after = service.list_all().await.unwrap();
+        let all_links_after = service
+            .list_links(LinkQuery::default())
+            .await
+            .unwrap()
+            .links;
         assert_eq!(all_links_after.len(), 2);
 
         // Verify the right links remain
@@ -159,17 +549,23 @@ mod tests {
     async fn test_cleanup_no_expired_links() {
         // Setup in-memory database
         let pool = init_db("sqlite::memory:").await.unwrap();
-        let repo = LinkRepository::new(pool);
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
         let service = LinkService::new(repo, "http://test.local".to_string());
 
         // Create only valid links
         service
-            .create_link("https://valid1.com", Some(Ttl::OneWeek))
+            .create_link(
+                "https://valid1.com",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    ..NewLink::default()
+                },
+            )
             .await
             .unwrap();
 
         service
-            .create_link("https://valid2.com", None)
+            .create_link("https://valid2.com", NewLink::default())
             .await
             .unwrap();
 
@@ -180,7 +576,828 @@ mod tests {
         assert_eq!(deleted_count, 0);
 
         // Verify both links still exist
-        let all_links = service.list_all().await.unwrap();
+        let all_links = service
+            .list_links(LinkQuery::default())
+            .await
+            .unwrap()
+            .links;
         assert_eq!(all_links.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_single_use_link() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com/onboarding",
+                NewLink {
+                    max_clicks: Some(1),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(link.remaining_clicks, Some(1));
+
+        service.resolve(&link.short_code).await.unwrap();
+        let result = service.resolve(&link.short_code).await;
+        assert!(matches!(result, Err(AppError::LinkExhausted)));
+
+        let exhausted = service
+            .list_links(LinkQuery {
+                status: Some(LinkStatus::Expired),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap()
+            .links;
+        assert_eq!(exhausted.len(), 1);
+        assert_eq!(exhausted[0].remaining_clicks, Some(0));
+
+        // Used-up links are purged with the expired ones
+        assert_eq!(service.cleanup_expired().await.unwrap(), 1);
+        let result = service.resolve(&link.short_code).await;
+        assert!(matches!(result, Err(AppError::LinkNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_click_limit_holds_under_concurrent_redirects() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    max_clicks: Some(3),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..20 {
+            let service = service.clone();
+            let code = link.short_code.clone();
+            tasks.spawn(async move { service.resolve(&code).await });
+        }
+        let results = tasks.join_all().await;
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 3);
+        assert!(
+            results
+                .iter()
+                .filter_map(|r| r.as_ref().err())
+                .all(|e| matches!(e, AppError::LinkExhausted))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protected_link_counts_clicks_when_unlocked() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    password: Some("hunter2".to_string()),
+                    max_clicks: Some(1),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Showing the unlock form and failed attempts do not use the link up
+        let resolved = service.resolve(&link.short_code).await.unwrap();
+        let resolved_again = service.resolve(&link.short_code).await.unwrap();
+        let result = service
+            .check_password(resolved_again, "wrong".to_string())
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidPassword)));
+
+        service
+            .check_password(resolved, "hunter2".to_string())
+            .await
+            .unwrap();
+        let result = service.resolve(&link.short_code).await;
+        assert!(matches!(result, Err(AppError::LinkExhausted)));
+    }
+
+    #[tokio::test]
+    async fn test_form_post_to_plain_link_counts_one_click() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    max_clicks: Some(1),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // A form POST resolves the link, then checks the (empty) password
+        let resolved = service.resolve(&link.short_code).await.unwrap();
+        let unlocked = service
+            .check_password(resolved, String::new())
+            .await
+            .unwrap();
+        assert_eq!(unlocked.click_count, 1);
+
+        let result = service.resolve(&link.short_code).await;
+        assert!(matches!(result, Err(AppError::LinkExhausted)));
+    }
+
+    #[tokio::test]
+    async fn test_create_link_rejects_zero_max_clicks() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let result = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    max_clicks: Some(0),
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_link_not_active_before_launch() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let launch = Utc::now() + Duration::days(3);
+        let link = service
+            .create_link(
+                "https://example.com/campaign",
+                NewLink {
+                    max_clicks: Some(1),
+                    active_from: Some(launch),
+                    prelaunch_url: Some("https://example.com/teaser".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        match service.resolve(&link.short_code).await {
+            Err(AppError::LinkNotActive {
+                active_from,
+                prelaunch_url,
+            }) => {
+                assert_eq!(active_from, launch);
+                assert_eq!(
+                    prelaunch_url.unwrap().as_str(),
+                    "https://example.com/teaser"
+                );
+            }
+            other => panic!("expected LinkNotActive, got {:?}", other),
+        }
+
+        // Visits before launch do not count against the click limit
+        let scheduled = service
+            .list_links(LinkQuery {
+                status: Some(LinkStatus::Scheduled),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap()
+            .links;
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].remaining_clicks, Some(1));
+
+        let active = service
+            .list_links(LinkQuery {
+                status: Some(LinkStatus::Active),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap()
+            .links;
+        assert!(active.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_scheduled_link_validation() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        // Activation after expiry
+        let result = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    active_from: Some(Utc::now() + Duration::days(8)),
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        // Pre-launch URL without activation time
+        let result = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    prelaunch_url: Some("https://example.com/teaser".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        // Activation time in the past: active right away
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    active_from: Some(Utc::now() - Duration::hours(1)),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(service.resolve(&link.short_code).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_update_scheduled_link_cannot_expire_before_activation() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    active_from: Some(Utc::now() + Duration::days(2)),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let result = service
+            .update_link(
+                link.id,
+                LinkUpdate {
+                    ttl: Some(Ttl::After(Duration::days(1))),
+                    ..LinkUpdate::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        let updated = service
+            .update_link(
+                link.id,
+                LinkUpdate {
+                    ttl: Some(Ttl::OneWeek),
+                    ..LinkUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(updated.expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_create_link_enforces_ttl_bounds() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service =
+            LinkService::new(repo, "http://test.local".to_string()).with_ttl_bounds(TtlBounds {
+                min: Some(Duration::hours(1)),
+                max: Some(Duration::days(90)),
+            });
+
+        let create = |ttl: Option<Ttl>| {
+            service.create_link(
+                "https://example.com/event",
+                NewLink {
+                    ttl,
+                    ..NewLink::default()
+                },
+            )
+        };
+
+        let link = create(Some("36h".parse().unwrap())).await.unwrap();
+        let lifetime = link.expires_at.unwrap() - link.created_at;
+        assert_eq!(lifetime, Duration::hours(36));
+
+        let at = Utc::now() + Duration::days(10);
+        let link = create(Some(Ttl::At(at))).await.unwrap();
+        assert_eq!(link.expires_at, Some(at));
+
+        // Links without a TTL get the maximum one
+        let link = create(None).await.unwrap();
+        assert_eq!(
+            link.expires_at.unwrap() - link.created_at,
+            Duration::days(90)
+        );
+
+        for ttl in [
+            Ttl::After(Duration::minutes(30)),
+            Ttl::OneYear,
+            Ttl::Never,
+            Ttl::At(Utc::now() - Duration::hours(1)),
+        ] {
+            let result = create(Some(ttl)).await;
+            assert!(
+                matches!(result, Err(AppError::InvalidTtl(_))),
+                "{} should be rejected",
+                ttl
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_link_with_alias() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com/sale",
+                NewLink {
+                    alias: Some("spring-sale".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(link.short_code, "spring-sale");
+        assert_eq!(link.short_url, "http://test.local/spring-sale");
+
+        let resolved = service.resolve("spring-sale").await.unwrap();
+        assert_eq!(resolved.target_url.as_str(), "https://example.com/sale");
+    }
+
+    #[tokio::test]
+    async fn test_create_link_with_taken_alias() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        service
+            .create_link(
+                "https://example.com/a",
+                NewLink {
+                    alias: Some("spring-sale".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let result = service
+            .create_link(
+                "https://example.com/b",
+                NewLink {
+                    alias: Some("spring-sale".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::ShortCodeTaken(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_link_with_reserved_alias() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let result = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    alias: Some("health".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidAlias(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_link_with_password() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com/private",
+                NewLink {
+                    password: Some("hunter2".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(link.password_protected);
+
+        let resolved = service.resolve(&link.short_code).await.unwrap();
+        assert!(resolved.is_protected());
+        assert_ne!(resolved.password_hash.as_deref(), Some("hunter2"));
+
+        let result = service
+            .check_password(resolved.clone(), "wrong".to_string())
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidPassword)));
+
+        let unlocked = service
+            .check_password(resolved, "hunter2".to_string())
+            .await
+            .unwrap();
+        assert_eq!(unlocked.target_url.as_str(), "https://example.com/private");
+
+        let result = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    password: Some(String::new()),
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_link_target_and_expiry() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://old.example.com",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let updated = service
+            .update_link(
+                link.id,
+                LinkUpdate {
+                    url: Some("https://new.example.com".to_string()),
+                    ttl: Some(Ttl::Never),
+                    ..LinkUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.short_code, link.short_code);
+        assert_eq!(updated.target_url, "https://new.example.com/");
+        assert!(updated.expires_at.is_none());
+        assert!(updated.updated_at >= link.updated_at);
+
+        let resolved = service.resolve(&link.short_code).await.unwrap();
+        assert_eq!(resolved.target_url.as_str(), "https://new.example.com/");
+        assert_eq!(resolved.updated_at, updated.updated_at);
+    }
+
+    #[tokio::test]
+    async fn test_update_link_keeps_unspecified_fields() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let updated = service
+            .update_link(
+                link.id,
+                LinkUpdate {
+                    url: Some("https://example.org".to_string()),
+                    ..LinkUpdate::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.expires_at, link.expires_at);
+    }
+
+    #[tokio::test]
+    async fn test_set_variants_splits_and_unsplits_link() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link("https://example.com/landing", NewLink::default())
+            .await
+            .unwrap();
+        let variants: Vec<Variant> = serde_json::from_value(serde_json::json!([
+            { "name": "a", "url": "https://example.com/landing", "weight": 50 },
+            { "name": "b", "url": "https://example.com/landing-v2", "weight": 50 }
+        ]))
+        .unwrap();
+
+        let split = service
+            .set_variants(link.id, variants.clone())
+            .await
+            .unwrap();
+        assert_eq!(split.variants, variants);
+        let resolved = service.resolve(&link.short_code).await.unwrap();
+        assert_eq!(resolved.variants, variants);
+
+        // A single variant is not a split
+        let result = service.set_variants(link.id, variants[..1].to_vec()).await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        service.set_variants(link.id, Vec::new()).await.unwrap();
+        let resolved = service.resolve(&link.short_code).await.unwrap();
+        assert!(resolved.variants.is_empty());
+
+        let result = service.set_variants(Uuid::new_v4(), variants).await;
+        assert!(matches!(result, Err(AppError::LinkNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_with_suffix_requires_passthrough() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let link = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    max_clicks: Some(1),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Not found without passthrough, and the click is not used up
+        let result = service.resolve_with_suffix(&link.short_code).await;
+        assert!(matches!(result, Err(AppError::LinkNotFound)));
+
+        let update = LinkUpdate {
+            passthrough: Some(Passthrough::TargetWins),
+            ..LinkUpdate::default()
+        };
+        let updated = service.update_link(link.id, update).await.unwrap();
+        assert_eq!(updated.passthrough, Passthrough::TargetWins);
+
+        let resolved = service.resolve_with_suffix(&link.short_code).await.unwrap();
+        assert_eq!(resolved.click_count, 1);
+
+        // The unlock form would drop the forwarded path
+        let result = service
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    password: Some("s3cret".to_string()),
+                    passthrough: Passthrough::KeepBoth,
+                    ..NewLink::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_nonexistent_link() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let result = service
+            .update_link(
+                Uuid::new_v4(),
+                LinkUpdate {
+                    url: Some("https://example.com".to_string()),
+                    ..LinkUpdate::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::LinkNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_list_links_paginates_with_cursor() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        for i in 0..5 {
+            service
+                .create_link(&format!("https://example.com/{}", i), NewLink::default())
+                .await
+                .unwrap();
+        }
+
+        let mut query = LinkQuery {
+            limit: 2,
+            ..LinkQuery::default()
+        };
+        let mut seen = Vec::new();
+        let mut pages = 0;
+        loop {
+            let page = service.list_links(query.clone()).await.unwrap();
+            pages += 1;
+            seen.extend(page.links.into_iter().map(|l| l.id));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(LinkCursor::decode(&cursor).unwrap()),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(seen.len(), 5);
+        seen.dedup();
+        assert_eq!(seen.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_list_links_filters() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo.clone(), "http://test.local".to_string());
+
+        let now = Utc::now();
+        repo.create(&Link {
+            expires_at: Some(now - Duration::hours(1)),
+            ..Link::new(
+                ShortCode::from_existing("old-promo".to_string()),
+                Url::parse("https://shop.Example.com/old").unwrap(),
+                now,
+            )
+        })
+        .await
+        .unwrap();
+        service
+            .create_link(
+                "https://example.com/new",
+                NewLink {
+                    ttl: Some(Ttl::OneWeek),
+                    alias: Some("new-promo".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+        service
+            .create_link(
+                "https://notexample.com",
+                NewLink {
+                    alias: Some("docs".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let codes = |page: LinkPage| {
+            let mut codes: Vec<String> = page.links.into_iter().map(|l| l.short_code).collect();
+            codes.sort();
+            codes
+        };
+
+        let expired = service
+            .list_links(LinkQuery {
+                status: Some(LinkStatus::Expired),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(codes(expired), vec!["old-promo"]);
+
+        let never = service
+            .list_links(LinkQuery {
+                status: Some(LinkStatus::NeverExpires),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(codes(never), vec!["docs"]);
+
+        let by_domain = service
+            .list_links(LinkQuery {
+                domain: Some("EXAMPLE.com".to_string()),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(codes(by_domain), vec!["new-promo", "old-promo"]);
+
+        let by_prefix = service
+            .list_links(LinkQuery {
+                short_code_prefix: Some("new".to_string()),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(codes(by_prefix), vec!["new-promo"]);
+
+        let case_sensitive = service
+            .list_links(LinkQuery {
+                short_code_prefix: Some("NEW".to_string()),
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap();
+        assert!(case_sensitive.links.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_links_sorted_by_short_code() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        for alias in ["bravo", "alpha", "charlie"] {
+            service
+                .create_link(
+                    "https://example.com",
+                    NewLink {
+                        alias: Some(alias.to_string()),
+                        ..NewLink::default()
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let page = service
+            .list_links(LinkQuery {
+                sort: LinkSort::ShortCode,
+                order: SortOrder::Asc,
+                ..LinkQuery::default()
+            })
+            .await
+            .unwrap();
+        let codes: Vec<String> = page.links.into_iter().map(|l| l.short_code).collect();
+        assert_eq!(codes, vec!["alpha", "bravo", "charlie"]);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_links_rejects_invalid_query() {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repo = Arc::new(SqliteLinkRepository::new(pool));
+        let service = LinkService::new(repo, "http://test.local".to_string());
+
+        let result = service
+            .list_links(LinkQuery {
+                limit: MAX_PAGE_SIZE + 1,
+                ..LinkQuery::default()
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        let result = service
+            .list_links(LinkQuery {
+                sort: LinkSort::ShortCode,
+                cursor: Some(LinkCursor {
+                    sort: LinkSort::CreatedAt,
+                    key: Utc::now().to_rfc3339(),
+                    id: Uuid::new_v4(),
+                }),
+                ..LinkQuery::default()
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+    }
 }
@@ -1,23 +1,126 @@
+use std::sync::Arc;
+use std::time::Instant;
+
 use crate::config::Config;
 use crate::error::{AppError, AppResult};
-use crate::qr::QrGenerator;
+use crate::metrics;
+use crate::qr::{
+    QrCache, QrCacheConfig, QrFormat, QrGenerator, QrStyle, RenderPool, RenderPoolError,
+};
 
 /// Service for QR code generation operations.
 #[derive(Clone)]
 pub struct QrService {
-    generator: QrGenerator,
+    generator: Arc<QrGenerator>,
+    cache: Arc<QrCache>,
+    pool: Arc<RenderPool>,
 }
 
 impl QrService {
     pub fn new(config: &Config) -> AppResult<Self> {
         let generator = QrGenerator::new(config.qr_size, config.qr_branding_logo.clone())
+            .map_err(AppError::QrGeneration)?
+            .with_verification(config.qr_verify);
+
+        // Reject a misconfigured QR_SIZE at startup rather than on every request
+        generator
+            .validate(&generator.default_style())
+            .map_err(AppError::QrGeneration)?;
+
+        let cache = QrCache::new(
+            &QrCacheConfig {
+                max_entries: config.qr_cache_entries,
+                max_bytes: config.qr_cache_max_bytes,
+                dir: config.qr_cache_dir.clone(),
+                disk_max_bytes: config.qr_cache_disk_max_bytes,
+            },
+            generator.logo_fingerprint(),
+        )
+        .map_err(AppError::QrGeneration)?;
+
+        let pool = RenderPool::new(config.qr_render_workers, config.qr_render_queue)
             .map_err(AppError::QrGeneration)?;
 
-        Ok(Self { generator })
+        Ok(Self {
+            generator: Arc::new(generator),
+            cache: Arc::new(cache),
+            pool: Arc::new(pool),
+        })
     }
 
-    /// Generate a QR code PNG for a raw URL (no shortening).
-    pub fn generate_for_url(&self, url: &str) -> AppResult<Vec<u8>> {
-        self.generator.generate(url).map_err(AppError::QrGeneration)
+    /// Default rendering style from the server configuration.
+    pub fn default_style(&self) -> QrStyle {
+        self.generator.default_style()
     }
+
+    /// Generate a QR code image for a raw URL (no shortening).
+    /// Renders are cached, so repeated requests for the same code are cheap.
+    /// Rendering runs on the render pool; when it is saturated the request is
+    /// rejected with [`AppError::QrRenderBusy`].
+    pub async fn generate_for_url(
+        &self,
+        url: &str,
+        format: QrFormat,
+        style: &QrStyle,
+    ) -> AppResult<Vec<u8>> {
+        self.generator
+            .validate(style)
+            .map_err(AppError::InvalidQrStyle)?;
+
+        let key = self.cache.key(url, format, style);
+        if let Some(data) = self.cache.get_from_memory(&key) {
+            metrics::QR_CACHE_HITS.with_label_values(&["memory"]).inc();
+            return Ok(data);
+        }
+
+        let generator = Arc::clone(&self.generator);
+        let cache = Arc::clone(&self.cache);
+        let url = url.to_string();
+        let style = style.clone();
+        self.pool
+            .run(move || render(&generator, &cache, &key, &url, format, &style))
+            .await
+            .map_err(|e| match e {
+                RenderPoolError::Saturated => {
+                    metrics::QR_RENDER_REJECTIONS.inc();
+                    AppError::QrRenderBusy
+                }
+                RenderPoolError::Failed => {
+                    AppError::QrGeneration("QR render worker failed".to_string())
+                }
+            })?
+    }
+}
+
+/// Render a QR code, or read it from the on-disk cache. Runs on the render pool.
+fn render(
+    generator: &QrGenerator,
+    cache: &QrCache,
+    key: &str,
+    content: &str,
+    format: QrFormat,
+    style: &QrStyle,
+) -> AppResult<Vec<u8>> {
+    if let Some((data, tier)) = cache.get(key, format) {
+        metrics::QR_CACHE_HITS
+            .with_label_values(&[tier.as_str()])
+            .inc();
+        return Ok(data);
+    }
+    metrics::QR_CACHE_MISSES.inc();
+
+    let started = Instant::now();
+    let data = generator
+        .generate(content, format, style)
+        .map_err(AppError::QrGeneration)?;
+
+    metrics::QR_RENDER_SECONDS
+        .with_label_values(&[format.as_str()])
+        .observe(started.elapsed().as_secs_f64());
+    metrics::QR_GENERATED
+        .with_label_values(&[format.as_str()])
+        .inc();
+
+    cache.insert(key, format, &data);
+    Ok(data)
 }
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use url::Url;
+use uuid::Uuid;
+
+use crate::domain::{Link, MAX_RULES_PER_LINK, RedirectRule, Visitor, select_rule};
+use crate::error::{AppError, AppResult};
+use crate::repository::{LinkRepository, RedirectRuleRepository};
+
+/// Outcome of a link's redirect rules for a visitor.
+#[derive(Debug, Clone, Default)]
+pub struct RuleRouting {
+    /// Whether the link has rules, so its destination may depend on the visitor
+    pub has_rules: bool,
+    /// URL of the first matching rule, if any
+    pub destination: Option<Url>,
+}
+
+/// Service for managing per-link redirect rules and picking a visitor's destination.
+#[derive(Clone)]
+pub struct RedirectRuleService {
+    links: Arc<dyn LinkRepository>,
+    rules: Arc<dyn RedirectRuleRepository>,
+}
+
+impl RedirectRuleService {
+    pub fn new(links: Arc<dyn LinkRepository>, rules: Arc<dyn RedirectRuleRepository>) -> Self {
+        Self { links, rules }
+    }
+
+    /// Get the redirect rules of a link, in evaluation order.
+    pub async fn get_rules(&self, link_id: Uuid) -> AppResult<Vec<RedirectRule>> {
+        self.ensure_link_exists(link_id).await?;
+        self.rules.list(link_id).await
+    }
+
+    /// Replace the redirect rules of a link. An empty list removes all rules.
+    pub async fn set_rules(
+        &self,
+        link_id: Uuid,
+        rules: Vec<RedirectRule>,
+    ) -> AppResult<Vec<RedirectRule>> {
+        if rules.len() > MAX_RULES_PER_LINK {
+            return Err(AppError::InvalidRequest(format!(
+                "A link can have at most {} rules",
+                MAX_RULES_PER_LINK
+            )));
+        }
+        for (index, rule) in rules.iter().enumerate() {
+            rule.validate()
+                .map_err(|e| AppError::InvalidRequest(format!("Rule {}: {}", index + 1, e)))?;
+        }
+
+        self.ensure_link_exists(link_id).await?;
+        self.rules.replace(link_id, &rules).await?;
+        Ok(rules)
+    }
+
+    /// Route a visitor through a link's rules: the URL of the first matching rule, if any.
+    pub async fn route(&self, link: &Link, visitor: &Visitor) -> AppResult<RuleRouting> {
+        let rules = self.rules.list(link.id).await?;
+        Ok(RuleRouting {
+            has_rules: !rules.is_empty(),
+            destination: select_rule(&rules, visitor).cloned(),
+        })
+    }
+
+    async fn ensure_link_exists(&self, link_id: Uuid) -> AppResult<()> {
+        self.links
+            .find_by_id(link_id)
+            .await?
+            .ok_or(AppError::LinkNotFound)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ShortCode;
+    use crate::repository::{Repositories, init_db};
+    use chrono::Utc;
+
+    async fn setup() -> (RedirectRuleService, Link) {
+        let pool = init_db("sqlite::memory:").await.unwrap();
+        let repos = Repositories::sqlite(pool);
+        let link = Link::new(
+            ShortCode::generate(),
+            Url::parse("https://example.com").unwrap(),
+            Utc::now(),
+        );
+        repos.links.create(&link).await.unwrap();
+        (RedirectRuleService::new(repos.links, repos.rules), link)
+    }
+
+    fn rule(json: serde_json::Value) -> RedirectRule {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rules_pick_destination() {
+        let (service, link) = setup().await;
+        service
+            .set_rules(
+                link.id,
+                vec![
+                    rule(serde_json::json!({ "platforms": ["ios"], "url": "https://apps.apple.com/app/id1" })),
+                    rule(serde_json::json!({ "languages": ["fr"], "url": "https://example.com/fr" })),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let iphone = Visitor::from_headers(
+            Some("Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X)"),
+            None,
+            Utc::now(),
+        );
+        let french = Visitor::from_headers(None, Some("fr-FR,fr;q=0.9"), Utc::now());
+        let other = Visitor::from_headers(None, None, Utc::now());
+
+        let url = service.route(&link, &iphone).await.unwrap().destination;
+        assert_eq!(url.unwrap().as_str(), "https://apps.apple.com/app/id1");
+        let url = service.route(&link, &french).await.unwrap().destination;
+        assert_eq!(url.unwrap().as_str(), "https://example.com/fr");
+        let routing = service.route(&link, &other).await.unwrap();
+        assert!(routing.has_rules);
+        assert!(routing.destination.is_none());
+
+        // Replacing with no rules restores the plain redirect
+        service.set_rules(link.id, Vec::new()).await.unwrap();
+        assert!(service.get_rules(link.id).await.unwrap().is_empty());
+        let routing = service.route(&link, &iphone).await.unwrap();
+        assert!(!routing.has_rules);
+        assert!(routing.destination.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_rules_validation() {
+        let (service, link) = setup().await;
+
+        let no_condition = rule(serde_json::json!({ "url": "https://example.com/x" }));
+        let result = service.set_rules(link.id, vec![no_condition]).await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        let too_many = vec![
+            rule(
+                serde_json::json!({ "platforms": ["android"], "url": "https://example.com/x" })
+            );
+            MAX_RULES_PER_LINK + 1
+        ];
+        let result = service.set_rules(link.id, too_many).await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        let result = service.get_rules(Uuid::new_v4()).await;
+        assert!(matches!(result, Err(AppError::LinkNotFound)));
+    }
+}
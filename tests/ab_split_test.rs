@@ -0,0 +1,92 @@
+mod common;
+
+use serde_json::{Value, json};
+
+async fn create_split_link(client: &reqwest::Client, base: &str) -> Value {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({
+            "url": "https://example.com/landing",
+            "variants": [
+                { "name": "a", "url": "https://example.com/landing", "weight": 50 },
+                { "name": "b", "url": "https://example.com/landing-v2", "weight": 50 }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_split_link_assigns_sticky_variant() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let link = create_split_link(&client, &base).await;
+    let code = link["short_code"].as_str().unwrap();
+    assert_eq!(link["variants"].as_array().unwrap().len(), 2);
+
+    // First visit: drawn a variant and told to keep it
+    let response = client
+        .get(format!("{}/{}", base, code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    let location = response.headers()["location"].to_str().unwrap().to_string();
+    let cookie = response.headers()["set-cookie"].to_str().unwrap();
+    assert!(cookie.contains(&format!("Path=/{}", code)));
+    let variant = if location.ends_with("-v2") { "b" } else { "a" };
+    assert!(cookie.starts_with(&format!("qrlink_variant={};", variant)));
+
+    // Returning visitors keep their variant
+    for _ in 0..5 {
+        let response = client
+            .get(format!("{}/{}", base, code))
+            .header("cookie", format!("qrlink_variant={}", variant))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["location"], location.as_str());
+        assert!(response.headers().get("set-cookie").is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_split_link_variants_can_be_replaced() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let link = create_split_link(&client, &base).await;
+    let id = link["id"].as_str().unwrap();
+
+    let response = client
+        .put(format!("{}/api/v1/links/{}/variants", base, id))
+        .json(&json!({ "variants": [{ "name": "a", "url": "https://example.com", "weight": 1 }] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = client
+        .put(format!("{}/api/v1/links/{}/variants", base, id))
+        .json(&json!({ "variants": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert!(body.get("variants").is_none());
+
+    let response = client
+        .get(format!("{}/{}", base, link["short_code"].as_str().unwrap()))
+        .header("cookie", "qrlink_variant=b")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["location"],
+        "https://example.com/landing"
+    );
+    assert!(response.headers().get("set-cookie").is_none());
+}
@@ -1,8 +1,9 @@
 use qrlink::{
-    domain::Ttl,
-    repository::{LinkRepository, init_db},
-    service::LinkService,
+    domain::{LinkQuery, Ttl},
+    repository::MemoryLinkRepository,
+    service::{LinkService, NewLink},
 };
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Test that list and delete functions work at the service level
@@ -10,34 +11,54 @@ use uuid::Uuid;
 
 #[tokio::test]
 async fn test_list_all_links_service_level() {
-    let pool = init_db("sqlite::memory:").await.unwrap();
-    let repo = LinkRepository::new(pool);
+    let repo = Arc::new(MemoryLinkRepository::new());
     let service = LinkService::new(repo, "http://test.local".to_string());
 
     // Create test links
     service
-        .create_link("https://example1.com", Some(Ttl::OneWeek))
+        .create_link(
+            "https://example1.com",
+            NewLink {
+                ttl: Some(Ttl::OneWeek),
+                ..NewLink::default()
+            },
+        )
         .await
         .unwrap();
     service
-        .create_link("https://example2.com", Some(Ttl::OneMonth))
+        .create_link(
+            "https://example2.com",
+            NewLink {
+                ttl: Some(Ttl::OneMonth),
+                ..NewLink::default()
+            },
+        )
         .await
         .unwrap();
 
     // List should work at service level
-    let links = service.list_all().await.unwrap();
+    let links = service
+        .list_links(LinkQuery::default())
+        .await
+        .unwrap()
+        .links;
     assert_eq!(links.len(), 2);
 }
 
 #[tokio::test]
 async fn test_delete_link_service_level() {
-    let pool = init_db("sqlite::memory:").await.unwrap();
-    let repo = LinkRepository::new(pool);
+    let repo = Arc::new(MemoryLinkRepository::new());
     let service = LinkService::new(repo, "http://test.local".to_string());
 
     // Create a test link
     let link = service
-        .create_link("https://example.com", Some(Ttl::OneWeek))
+        .create_link(
+            "https://example.com",
+            NewLink {
+                ttl: Some(Ttl::OneWeek),
+                ..NewLink::default()
+            },
+        )
         .await
         .unwrap();
     let link_id = link.id;
@@ -46,14 +67,17 @@ async fn test_delete_link_service_level() {
     service.delete_link(link_id).await.unwrap();
 
     // Link should no longer be in list
-    let links = service.list_all().await.unwrap();
+    let links = service
+        .list_links(LinkQuery::default())
+        .await
+        .unwrap()
+        .links;
     assert_eq!(links.len(), 0);
 }
 
 #[tokio::test]
 async fn test_delete_nonexistent_link_service_level() {
-    let pool = init_db("sqlite::memory:").await.unwrap();
-    let repo = LinkRepository::new(pool);
+    let repo = Arc::new(MemoryLinkRepository::new());
     let service = LinkService::new(repo, "http://test.local".to_string());
 
     // Try to delete a link that doesn't exist
@@ -0,0 +1,162 @@
+mod common;
+
+use serde_json::{Value, json};
+
+const ADMIN_SECRET: &str = "test-admin-secret";
+
+/// Start the full HTTP stack with an admin secret and return its base URL.
+async fn spawn_app(require_api_key: bool) -> String {
+    common::spawn_app(qrlink::config::Config {
+        admin_secret: Some(ADMIN_SECRET.to_string()),
+        require_api_key,
+        ..common::test_config()
+    })
+    .await
+}
+
+/// Issue an API key with the given scopes using the admin secret.
+async fn issue_key(client: &reqwest::Client, base: &str, scopes: &[&str]) -> (String, String) {
+    let response = client
+        .post(format!("{}/api/v1/keys", base))
+        .header("X-Admin-Secret", ADMIN_SECRET)
+        .json(&json!({ "name": "test", "scopes": scopes }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let body: Value = response.json().await.unwrap();
+    (
+        body["id"].as_str().unwrap().to_string(),
+        body["key"].as_str().unwrap().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_list_links_requires_credentials() {
+    let base = spawn_app(false).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/v1/links", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = client
+        .get(format!("{}/api/v1/links", base))
+        .header("X-Admin-Secret", "wrong")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = client
+        .get(format!("{}/api/v1/links", base))
+        .header("X-Admin-Secret", ADMIN_SECRET)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_api_key_scopes_are_enforced() {
+    let base = spawn_app(false).await;
+    let client = reqwest::Client::new();
+    let (_, read_key) = issue_key(&client, &base, &["links:read"]).await;
+
+    let response = client
+        .get(format!("{}/api/v1/links", base))
+        .bearer_auth(&read_key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/links/00000000-0000-0000-0000-000000000000",
+            base
+        ))
+        .header("X-Api-Key", &read_key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "insufficient_scope");
+}
+
+#[tokio::test]
+async fn test_revoked_api_key_is_rejected() {
+    let base = spawn_app(false).await;
+    let client = reqwest::Client::new();
+    let (id, key) = issue_key(&client, &base, &["links:read"]).await;
+
+    let response = client
+        .delete(format!("{}/api/v1/keys/{}", base, id))
+        .header("X-Admin-Secret", ADMIN_SECRET)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    let response = client
+        .get(format!("{}/api/v1/links", base))
+        .bearer_auth(&key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_api_keys_cannot_manage_keys() {
+    let base = spawn_app(false).await;
+    let client = reqwest::Client::new();
+    let (_, key) = issue_key(&client, &base, &["links:read", "links:delete"]).await;
+
+    let response = client
+        .get(format!("{}/api/v1/keys", base))
+        .bearer_auth(&key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+}
+
+#[tokio::test]
+async fn test_create_link_public_unless_api_key_required() {
+    let client = reqwest::Client::new();
+    let payload = json!({ "url": "https://example.com" });
+
+    let open = spawn_app(false).await;
+    let response = client
+        .post(format!("{}/api/v1/links", open))
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let locked = spawn_app(true).await;
+    let response = client
+        .post(format!("{}/api/v1/links", locked))
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let (_, key) = issue_key(&client, &locked, &["links:create"]).await;
+    let response = client
+        .post(format!("{}/api/v1/links", locked))
+        .bearer_auth(&key)
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+}
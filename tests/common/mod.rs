@@ -0,0 +1,80 @@
+//! Helpers shared by the HTTP integration tests.
+#![allow(dead_code)]
+
+use qrlink::{
+    config::Config,
+    domain::{RedirectType, TtlBounds},
+    http::{AppState, create_router},
+    rate_limit::RateLimiter,
+    repository::{Repositories, init_db},
+    service::{AnalyticsService, ApiKeyService, LinkService, QrService, RedirectRuleService},
+};
+
+/// Configuration for tests: in-memory database, no admin secret, generous rate limit.
+pub fn test_config() -> Config {
+    Config {
+        database_url: "sqlite::memory:".to_string(),
+        base_url: "http://test.local".to_string(),
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        rate_limit_per_minute: 1000,
+        qr_branding_logo: None,
+        qr_size: 256,
+        qr_verify: false,
+        qr_cache_entries: 100,
+        qr_cache_max_bytes: 4 * 1024 * 1024,
+        qr_cache_dir: None,
+        qr_cache_disk_max_bytes: 0,
+        qr_render_workers: 2,
+        qr_render_queue: 16,
+        cleanup_interval_minutes: 0,
+        admin_secret: None,
+        require_api_key: false,
+        ip_hash_salt: "test-salt".to_string(),
+        unlock_attempts_per_ip: 5,
+        unlock_attempts_per_link: 20,
+        min_ttl: None,
+        max_ttl: None,
+        default_redirect_type: RedirectType::Temporary,
+    }
+}
+
+/// Start the full HTTP stack on an ephemeral port and return its base URL.
+pub async fn spawn_app(config: Config) -> String {
+    let repos = Repositories::sqlite(init_db(&config.database_url).await.unwrap());
+
+    let redirect_rule_service = RedirectRuleService::new(repos.links.clone(), repos.rules);
+
+    let app = create_router(AppState {
+        link_service: LinkService::new(repos.links, config.base_url.clone()).with_ttl_bounds(
+            TtlBounds {
+                min: config.min_ttl,
+                max: config.max_ttl,
+            },
+        ),
+        qr_service: QrService::new(&config).unwrap(),
+        analytics_service: AnalyticsService::new(repos.clicks, config.ip_hash_salt.clone()),
+        api_key_service: ApiKeyService::new(repos.api_keys),
+        redirect_rule_service,
+        rate_limiter: RateLimiter::new(config.rate_limit_per_minute),
+        unlock_ip_limiter: RateLimiter::new(config.unlock_attempts_per_ip),
+        unlock_link_limiter: RateLimiter::new(config.unlock_attempts_per_link),
+        admin_secret: config.admin_secret.clone(),
+        require_api_key: config.require_api_key,
+        default_redirect_type: config.default_redirect_type,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    format!("http://{}", addr)
+}
+
+/// HTTP client that does not follow redirects.
+pub fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+}
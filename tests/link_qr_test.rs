@@ -0,0 +1,102 @@
+mod common;
+
+use serde_json::{Value, json};
+
+async fn create_link(client: &reqwest::Client, base: &str) -> Value {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://example.com/landing" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_short_link_qr_is_cacheable() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let link = create_link(&client, &base).await;
+    let qr_url = format!("{}/{}/qr", base, link["short_code"].as_str().unwrap());
+
+    let response = client.get(&qr_url).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    assert_eq!(response.headers()["cache-control"], "public, max-age=86400");
+    let etag = response.headers()["etag"].clone();
+    let png = response.bytes().await.unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    let response = client
+        .get(&qr_url)
+        .header("If-None-Match", etag.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 304);
+    assert_eq!(response.headers()["etag"], etag);
+
+    // Other options render another image
+    let response = client
+        .get(format!("{}?format=svg&size=512", qr_url))
+        .header("If-None-Match", etag.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/svg+xml");
+    assert_ne!(response.headers()["etag"], etag);
+
+    let response = client
+        .get(format!("{}?size=10", qr_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = client
+        .get(format!("{}/unknown/qr", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_link_qr_by_id() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let link = create_link(&client, &base).await;
+
+    let response = client
+        .get(format!(
+            "{}/api/v1/links/{}/qr",
+            base,
+            link["id"].as_str().unwrap()
+        ))
+        .header("Accept", "image/svg+xml")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/svg+xml");
+    assert_eq!(
+        response.headers()["cache-control"],
+        "private, max-age=86400"
+    );
+
+    let short_code_qr = client
+        .get(format!(
+            "{}/{}/qr?format=svg",
+            base,
+            link["short_code"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.bytes().await.unwrap(),
+        short_code_qr.bytes().await.unwrap()
+    );
+}
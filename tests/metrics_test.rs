@@ -0,0 +1,66 @@
+mod common;
+
+use serde_json::json;
+
+#[tokio::test]
+async fn test_metrics_endpoint_reports_activity() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://example.com", "alias": "metrics-test" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let response = client
+        .get(format!("{}/metrics-test", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+
+    let response = client
+        .get(format!("{}/does-not-exist", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    // The second request is served from the render cache
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{}/api/v1/qr", base))
+            .json(&json!({ "url": "https://example.com", "format": "svg" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    let response = client
+        .get(format!("{}/metrics", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+
+    let body = response.text().await.unwrap();
+    assert!(body.contains("qrlink_links_created_total 1"));
+    assert!(body.contains("qrlink_redirects_total{outcome=\"ok\"} 1"));
+    assert!(body.contains("qrlink_redirects_total{outcome=\"not_found\"} 1"));
+    assert!(body.contains("qrlink_qr_generated_total{format=\"svg\"} 1"));
+    assert!(body.contains("qrlink_qr_render_duration_seconds_count{format=\"svg\"} 1"));
+    assert!(body.contains("qrlink_qr_cache_hits_total{tier=\"memory\"} 1"));
+    assert!(body.contains("qrlink_qr_cache_misses_total 1"));
+    assert!(body.contains("route=\"/api/v1/links\""));
+    assert!(body.contains("route=\"/{short_code}\""));
+}
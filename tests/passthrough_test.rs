@@ -0,0 +1,141 @@
+mod common;
+
+use serde_json::{Value, json};
+
+async fn create_link(client: &reqwest::Client, base: &str, body: Value) -> Value {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+async fn visit(
+    client: &reqwest::Client,
+    base: &str,
+    link: &Value,
+    rest: &str,
+) -> reqwest::Response {
+    client
+        .get(format!(
+            "{}/{}{}",
+            base,
+            link["short_code"].as_str().unwrap(),
+            rest
+        ))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_passthrough_forwards_path_and_query() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({
+            "url": "https://docs.example.com/v2/?ref=qr",
+            "passthrough": "target_wins"
+        }),
+    )
+    .await;
+    assert_eq!(link["passthrough"], "target_wins");
+
+    let response = visit(&client, &base, &link, "/guide/install?ref=mail&page=2").await;
+    assert_eq!(response.status(), 307);
+    assert_eq!(
+        response.headers()["location"],
+        "https://docs.example.com/v2/guide/install?ref=qr&page=2"
+    );
+
+    // The bare short code still forwards the query
+    let response = visit(&client, &base, &link, "?page=3").await;
+    assert_eq!(
+        response.headers()["location"],
+        "https://docs.example.com/v2/?ref=qr&page=3"
+    );
+
+    // Switch the conflict policy
+    let id = link["id"].as_str().unwrap();
+    let response = client
+        .patch(format!("{}/api/v1/links/{}", base, id))
+        .json(&json!({ "passthrough": "request_wins" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = visit(&client, &base, &link, "/faq?ref=mail").await;
+    assert_eq!(
+        response.headers()["location"],
+        "https://docs.example.com/v2/faq?ref=mail"
+    );
+}
+
+#[tokio::test]
+async fn test_extra_path_not_found_without_passthrough() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/landing", "max_clicks": 1 }),
+    )
+    .await;
+    assert!(link.get("passthrough").is_none());
+
+    let response = visit(&client, &base, &link, "/docs/page").await;
+    assert_eq!(response.status(), 404);
+
+    // The query is ignored, and the 404 did not use up the single click
+    let response = visit(&client, &base, &link, "?ref=mail").await;
+    assert_eq!(response.status(), 307);
+    assert_eq!(
+        response.headers()["location"],
+        "https://example.com/landing"
+    );
+}
+
+#[tokio::test]
+async fn test_passthrough_does_not_forward_reserved_suffixes() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({
+            "url": "https://docs.example.com/v2/",
+            "passthrough": "target_wins"
+        }),
+    )
+    .await;
+
+    let response = visit(&client, &base, &link, "/qr").await;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/png");
+
+    let response = visit(&client, &base, &link, "/preview").await;
+    assert_eq!(response.status(), 200);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html")
+    );
+
+    // Only the exact suffixes are reserved
+    let response = visit(&client, &base, &link, "/qr/menu").await;
+    assert_eq!(response.status(), 307);
+    assert_eq!(
+        response.headers()["location"],
+        "https://docs.example.com/v2/qr/menu"
+    );
+}
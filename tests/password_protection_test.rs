@@ -0,0 +1,110 @@
+mod common;
+
+use serde_json::{Value, json};
+
+/// Create a password-protected link and return its short code.
+async fn create_protected_link(client: &reqwest::Client, base: &str) -> String {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://intranet.example.com/doc", "password": "s3cret" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["password_protected"], true);
+    assert!(body.get("password_hash").is_none());
+    body["short_code"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_protected_link_requires_password() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let code = create_protected_link(&client, &base).await;
+
+    // GET serves the unlock form instead of redirecting
+    let response = client
+        .get(format!("{}/{}", base, code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("location").is_none());
+    let html = response.text().await.unwrap();
+    assert!(html.contains(r#"type="password""#));
+
+    let response = client
+        .post(format!("{}/{}", base, code))
+        .form(&[("password", "wrong")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = client
+        .post(format!("{}/{}", base, code))
+        .form(&[("password", "s3cret")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 303);
+    assert_eq!(
+        response.headers()["location"],
+        "https://intranet.example.com/doc"
+    );
+}
+
+#[tokio::test]
+async fn test_failed_attempts_are_rate_limited() {
+    let base = common::spawn_app(qrlink::config::Config {
+        unlock_attempts_per_ip: 2,
+        ..common::test_config()
+    })
+    .await;
+    let client = common::client();
+    let code = create_protected_link(&client, &base).await;
+
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{}/{}", base, code))
+            .form(&[("password", "wrong")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+    }
+
+    // Even the right password is refused until the window resets
+    let response = client
+        .post(format!("{}/{}", base, code))
+        .form(&[("password", "s3cret")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 429);
+    assert!(response.headers().contains_key("retry-after"));
+}
+
+#[tokio::test]
+async fn test_unprotected_link_redirects_directly() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://example.com" }))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["password_protected"], false);
+
+    let response = client
+        .get(format!("{}/{}", base, body["short_code"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+}
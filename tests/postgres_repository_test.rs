@@ -0,0 +1,407 @@
+//! Runs the PostgreSQL backend against a local instance.
+//!
+//! Requires the `postgres` feature and `TEST_POSTGRES_URL`, e.g.:
+//! `TEST_POSTGRES_URL=postgres://postgres@localhost/qrlink_test cargo test --features postgres`
+//! Tests are skipped when the variable is not set.
+#![cfg(feature = "postgres")]
+
+use std::net::IpAddr;
+
+use chrono::{Duration, Utc};
+use qrlink::{
+    domain::{
+        LinkCursor, LinkQuery, LinkSort, LinkStatus, Passthrough, RedirectRule, RedirectType,
+        Scope, SortOrder, Ttl, Utm, Variant, Visitor,
+    },
+    error::AppError,
+    repository::{Repositories, init_pg_db},
+    service::{
+        AnalyticsService, ApiKeyService, LinkService, LinkUpdate, NewLink, RedirectRuleService,
+    },
+};
+
+async fn repositories() -> Option<Repositories> {
+    let url = std::env::var("TEST_POSTGRES_URL").ok()?;
+    let pool = init_pg_db(&url).await.unwrap();
+    sqlx::query("TRUNCATE links, clicks, api_keys, redirect_rules")
+        .execute(&pool)
+        .await
+        .unwrap();
+    Some(Repositories::postgres(pool))
+}
+
+// Tests share one database, so everything runs in a single test to avoid interference.
+#[tokio::test]
+async fn test_postgres_backend() {
+    let Some(repos) = repositories().await else {
+        eprintln!("TEST_POSTGRES_URL not set, skipping");
+        return;
+    };
+
+    let links = LinkService::new(repos.links.clone(), "http://test.local".to_string());
+
+    // Create, resolve and alias collisions
+    let link = links
+        .create_link(
+            "https://Shop.Example.com/a",
+            NewLink {
+                ttl: Some(Ttl::OneWeek),
+                alias: Some("spring-sale".to_string()),
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    let resolved = links.resolve("spring-sale").await.unwrap();
+    assert_eq!(resolved.id, link.id);
+    let taken = links
+        .create_link(
+            "https://example.com/b",
+            NewLink {
+                alias: Some("spring-sale".to_string()),
+                ..NewLink::default()
+            },
+        )
+        .await;
+    assert!(matches!(taken, Err(AppError::ShortCodeTaken(_))));
+
+    // Update
+    let updated = links
+        .update_link(
+            link.id,
+            LinkUpdate {
+                url: Some("https://example.org/new".to_string()),
+                ttl: Some(Ttl::Never),
+                redirect_type: Some(RedirectType::Permanent),
+                ..LinkUpdate::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(updated.expires_at.is_none());
+    let resolved = links.resolve(&link.short_code).await.unwrap();
+    assert_eq!(resolved.redirect_type, Some(RedirectType::Permanent));
+
+    for alias in ["alpha", "bravo", "Charlie"] {
+        links
+            .create_link(
+                "https://example.com",
+                NewLink {
+                    ttl: Some(Ttl::OneMonth),
+                    alias: Some(alias.to_string()),
+                    password: (alias == "bravo").then(|| "s3cret".to_string()),
+                    ..NewLink::default()
+                },
+            )
+            .await
+            .unwrap();
+    }
+    let protected = links.resolve("bravo").await.unwrap();
+    assert!(protected.verify_password("s3cret"));
+    assert!(!links.resolve("alpha").await.unwrap().is_protected());
+
+    // Filters
+    let by_domain = links
+        .list_links(LinkQuery {
+            domain: Some("example.com".to_string()),
+            ..LinkQuery::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(by_domain.links.len(), 3);
+
+    let never = links
+        .list_links(LinkQuery {
+            status: Some(LinkStatus::NeverExpires),
+            ..LinkQuery::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(never.links.len(), 1);
+
+    // Keyset pagination in byte order, as with SQLite
+    let mut query = LinkQuery {
+        sort: LinkSort::ShortCode,
+        order: SortOrder::Asc,
+        limit: 2,
+        ..LinkQuery::default()
+    };
+    let mut codes = Vec::new();
+    loop {
+        let page = links.list_links(query.clone()).await.unwrap();
+        codes.extend(page.links.into_iter().map(|l| l.short_code));
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(LinkCursor::decode(&cursor).unwrap()),
+            None => break,
+        }
+    }
+    assert_eq!(codes, vec!["Charlie", "alpha", "bravo", "spring-sale"]);
+
+    // Clicks
+    let analytics = AnalyticsService::new(repos.clicks.clone(), "salt".to_string());
+    let ip: IpAddr = "10.0.0.1".parse().unwrap();
+    analytics.record(link.id, ip, Some("https://news.example"), None, None);
+    analytics.record(link.id, ip, None, None, None);
+    analytics.flush().await;
+    let stats = analytics.stats(link.id).await.unwrap();
+    assert_eq!(stats.total_clicks, 2);
+    assert_eq!(stats.unique_visitors, 1);
+    assert_eq!(stats.daily[0].date, Utc::now().date_naive());
+
+    // API keys
+    let keys = ApiKeyService::new(repos.api_keys.clone());
+    let (created, key) = keys
+        .create_key(
+            "ci",
+            vec![Scope::LinksRead],
+            Some(Utc::now() + Duration::days(1)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(keys.authenticate(&key).await.unwrap().id, created.id);
+    keys.revoke_key(created.id).await.unwrap();
+    assert!(keys.authenticate(&key).await.is_err());
+
+    // Delete
+    links.delete_link(link.id).await.unwrap();
+    assert!(matches!(
+        links.resolve("spring-sale").await,
+        Err(AppError::LinkNotFound)
+    ));
+
+    // Click limits, consumed atomically and purged by cleanup
+    let limited = links
+        .create_link(
+            "https://example.com/onboarding",
+            NewLink {
+                max_clicks: Some(2),
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    let results = resolve_concurrently(&links, &limited.short_code, 10).await;
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2);
+    assert!(matches!(
+        links.resolve(&limited.short_code).await,
+        Err(AppError::LinkExhausted)
+    ));
+    assert_eq!(links.cleanup_expired().await.unwrap(), 1);
+
+    // Scheduled activation
+    let launch = Utc::now() + Duration::days(2);
+    let scheduled = links
+        .create_link(
+            "https://example.com/campaign",
+            NewLink {
+                active_from: Some(launch),
+                prelaunch_url: Some("https://example.com/teaser".to_string()),
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    match links.resolve(&scheduled.short_code).await {
+        Err(AppError::LinkNotActive { prelaunch_url, .. }) => {
+            assert_eq!(
+                prelaunch_url.unwrap().as_str(),
+                "https://example.com/teaser"
+            )
+        }
+        other => panic!("expected LinkNotActive, got {:?}", other),
+    }
+    let page = links
+        .list_links(LinkQuery {
+            status: Some(LinkStatus::Scheduled),
+            ..LinkQuery::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.links.len(), 1);
+    assert_eq!(
+        page.links[0].active_from.unwrap().timestamp_micros(),
+        launch.timestamp_micros()
+    );
+
+    // Redirect rules, removed along with their link
+    let rules = RedirectRuleService::new(repos.links.clone(), repos.rules.clone());
+    let routed = links
+        .create_link("https://example.com/app", NewLink::default())
+        .await
+        .unwrap();
+    let rule: RedirectRule = serde_json::from_value(serde_json::json!({
+        "platforms": ["android"],
+        "time": { "from": "08:00", "to": "20:00", "utc_offset": "+01:00" },
+        "url": "https://play.google.com/store/apps/details?id=app"
+    }))
+    .unwrap();
+    rules
+        .set_rules(routed.id, vec![rule.clone()])
+        .await
+        .unwrap();
+    assert_eq!(rules.get_rules(routed.id).await.unwrap(), vec![rule]);
+
+    let routed_link = links.resolve(&routed.short_code).await.unwrap();
+    let noon = "2030-06-01T11:00:00Z".parse().unwrap();
+    let android = Visitor::from_headers(Some("Mozilla/5.0 (Linux; Android 14)"), None, noon);
+    assert_eq!(
+        rules
+            .route(&routed_link, &android)
+            .await
+            .unwrap()
+            .destination
+            .unwrap()
+            .as_str(),
+        "https://play.google.com/store/apps/details?id=app"
+    );
+
+    links.delete_link(routed.id).await.unwrap();
+    assert!(repos.rules.list(routed.id).await.unwrap().is_empty());
+
+    // A/B split
+    let variants: Vec<Variant> = serde_json::from_value(serde_json::json!([
+        { "name": "a", "url": "https://example.com/a", "weight": 50 },
+        { "name": "b", "url": "https://example.com/b", "weight": 50 }
+    ]))
+    .unwrap();
+    let split = links
+        .create_link(
+            "https://example.com/a",
+            NewLink {
+                variants: variants.clone(),
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        links.resolve(&split.short_code).await.unwrap().variants,
+        variants
+    );
+    analytics.record(split.id, ip, None, None, Some("a"));
+    analytics.record(split.id, ip, None, None, Some("b"));
+    analytics.record(split.id, ip, None, None, Some("b"));
+    analytics.flush().await;
+    let stats = analytics.stats(split.id).await.unwrap();
+    let per_variant: Vec<_> = stats
+        .variants
+        .iter()
+        .map(|v| (v.variant.as_str(), v.clicks))
+        .collect();
+    assert_eq!(per_variant, vec![("a", 1), ("b", 2)]);
+
+    let unsplit = links.set_variants(split.id, Vec::new()).await.unwrap();
+    assert!(unsplit.variants.is_empty());
+    assert!(
+        links
+            .resolve(&split.short_code)
+            .await
+            .unwrap()
+            .variants
+            .is_empty()
+    );
+
+    // Passthrough
+    let prefix = links
+        .create_link(
+            "https://docs.example.com",
+            NewLink {
+                passthrough: Passthrough::KeepBoth,
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    let resolved = links.resolve_with_suffix(&prefix.short_code).await.unwrap();
+    assert_eq!(resolved.passthrough, Passthrough::KeepBoth);
+
+    let update = LinkUpdate {
+        passthrough: Some(Passthrough::Off),
+        ..LinkUpdate::default()
+    };
+    links.update_link(prefix.id, update).await.unwrap();
+    assert!(matches!(
+        links.resolve_with_suffix(&prefix.short_code).await,
+        Err(AppError::LinkNotFound)
+    ));
+
+    // UTM campaigns
+    let campaign = links
+        .create_link(
+            "https://example.com/sale?lang=en",
+            NewLink {
+                utm: Utm {
+                    source: Some("poster".to_string()),
+                    campaign: Some("spring-sale".to_string()),
+                    ..Utm::default()
+                },
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        campaign.target_url,
+        "https://example.com/sale?lang=en&utm_source=poster&utm_campaign=spring-sale"
+    );
+    let page = links
+        .list_links(LinkQuery {
+            campaign: Some("spring-sale".to_string()),
+            ..LinkQuery::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.links.len(), 1);
+    assert_eq!(page.links[0].id, campaign.id);
+
+    analytics.record(campaign.id, ip, None, None, None);
+    analytics.flush().await;
+    let stats = analytics.campaign_stats("spring-sale").await.unwrap();
+    assert_eq!((stats.links, stats.total_clicks), (1, 1));
+
+    // Interstitial
+    let gated = links
+        .create_link(
+            "https://example.com/gated",
+            NewLink {
+                interstitial: true,
+                max_clicks: Some(1),
+                ..NewLink::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(gated.interstitial);
+    // Not counted until continued
+    let resolved = links.resolve(&gated.short_code).await.unwrap();
+    assert_eq!(resolved.click_count, 0);
+    let continued = links.check_password(resolved, String::new()).await.unwrap();
+    assert_eq!(continued.click_count, 1);
+
+    let update = LinkUpdate {
+        interstitial: Some(false),
+        ..LinkUpdate::default()
+    };
+    let ungated = links.update_link(gated.id, update).await.unwrap();
+    assert!(!ungated.interstitial);
+    // Used up, so there is nothing left to preview
+    assert!(matches!(
+        links.get_by_short_code(&gated.short_code).await,
+        Err(AppError::LinkExhausted)
+    ));
+}
+
+/// Resolve a short code `n` times concurrently.
+async fn resolve_concurrently(
+    links: &LinkService,
+    short_code: &str,
+    n: usize,
+) -> Vec<Result<qrlink::domain::Link, AppError>> {
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..n {
+        let links = links.clone();
+        let code = short_code.to_string();
+        tasks.spawn(async move { links.resolve(&code).await });
+    }
+    tasks.join_all().await
+}
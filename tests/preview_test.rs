@@ -0,0 +1,137 @@
+mod common;
+
+use serde_json::{Value, json};
+
+async fn create_link(client: &reqwest::Client, base: &str, body: Value) -> Value {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_preview_page_does_not_count_as_redirect() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/landing", "max_clicks": 1 }),
+    )
+    .await;
+    let code = link["short_code"].as_str().unwrap();
+
+    for _ in 0..2 {
+        let response = client
+            .get(format!("{}/{}/preview", base, code))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["cache-control"], "no-store");
+        let html = response.text().await.unwrap();
+        assert!(html.contains("https://example.com/landing"));
+        assert!(html.contains("data:image/svg+xml;base64,"));
+    }
+
+    let response = client
+        .get(format!("{}/{}", base, code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+
+    let response = client
+        .get(format!("{}/unknown/preview", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_interstitial_link_redirects_on_continue() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/landing", "max_clicks": 1, "interstitial": true }),
+    )
+    .await;
+    assert_eq!(link["interstitial"], true);
+    let short_url = format!("{}/{}", base, link["short_code"].as_str().unwrap());
+
+    // Showing the page does not use up the click
+    for _ in 0..2 {
+        let response = client.get(&short_url).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("Continue"));
+    }
+
+    // The continue button posts an empty form
+    let response = client
+        .post(&short_url)
+        .form(&[] as &[(&str, &str)])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 303);
+    assert_eq!(
+        response.headers()["location"],
+        "https://example.com/landing"
+    );
+
+    let response = client.get(&short_url).send().await.unwrap();
+    assert_eq!(response.status(), 410);
+
+    // The continue button would drop a forwarded path
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({
+            "url": "https://example.com",
+            "interstitial": true,
+            "passthrough": "target_wins"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_preview_hides_destination_before_activation() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({
+            "url": "https://example.com/secret-campaign",
+            "active_from": chrono::Utc::now() + chrono::Duration::days(2),
+            "prelaunch_url": "https://example.com/teaser",
+        }),
+    )
+    .await;
+
+    let response = client
+        .get(format!(
+            "{}/{}/preview",
+            base,
+            link["short_code"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let html = response.text().await.unwrap();
+    assert!(!html.contains("secret-campaign"));
+    assert!(html.contains("hidden until this link opens"));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use serde_json::{Value, json};
+
+const IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+
+async fn create_link(client: &reqwest::Client, base: &str) -> Value {
+    client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://example.com/app" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_redirect_rules_route_by_platform_and_language() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let link = create_link(&client, &base).await;
+    let id = link["id"].as_str().unwrap();
+    let short_url = format!("{}/{}", base, link["short_code"].as_str().unwrap());
+
+    let response = client
+        .put(format!("{}/api/v1/links/{}/rules", base, id))
+        .json(&json!({
+            "rules": [
+                { "platforms": ["ios"], "url": "https://apps.apple.com/app/id123" },
+                { "platforms": ["android"], "url": "https://play.google.com/store/apps/details?id=app" },
+                { "languages": ["fr"], "url": "https://example.com/fr/app" },
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client
+        .get(format!("{}/api/v1/links/{}/rules", base, id))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["rules"].as_array().unwrap().len(), 3);
+    assert_eq!(body["rules"][0]["platforms"], json!(["ios"]));
+
+    let response = client
+        .get(&short_url)
+        .header("user-agent", IPHONE)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    assert_eq!(
+        response.headers()["location"],
+        "https://apps.apple.com/app/id123"
+    );
+
+    let response = client
+        .get(&short_url)
+        .header("accept-language", "fr-CA,fr;q=0.9,en;q=0.5")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["location"], "https://example.com/fr/app");
+
+    // No rule matches: fall back to the link's target
+    let response = client.get(&short_url).send().await.unwrap();
+    assert_eq!(response.headers()["location"], "https://example.com/app");
+}
+
+#[tokio::test]
+async fn test_invalid_redirect_rules_rejected() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+    let link = create_link(&client, &base).await;
+    let id = link["id"].as_str().unwrap();
+
+    let response = client
+        .put(format!("{}/api/v1/links/{}/rules", base, id))
+        .json(&json!({ "rules": [{ "url": "https://example.com/any" }] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = client
+        .put(format!(
+            "{}/api/v1/links/00000000-0000-0000-0000-000000000000/rules",
+            base
+        ))
+        .json(&json!({ "rules": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use qrlink::domain::RedirectType;
+use serde_json::{Value, json};
+
+async fn create_link(client: &reqwest::Client, base: &str, body: Value) -> Value {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+async fn visit(client: &reqwest::Client, base: &str, link: &Value) -> reqwest::Response {
+    client
+        .get(format!("{}/{}", base, link["short_code"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_redirect_type_per_link() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    // Server default: temporary, never cached
+    let link = create_link(&client, &base, json!({ "url": "https://example.com" })).await;
+    let response = visit(&client, &base, &link).await;
+    assert_eq!(response.status(), 307);
+    assert_eq!(response.headers()["cache-control"], "no-store");
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/legacy", "redirect_type": "found" }),
+    )
+    .await;
+    assert_eq!(link["redirect_type"], "found");
+    let response = visit(&client, &base, &link).await;
+    assert_eq!(response.status(), 302);
+    assert_eq!(response.headers()["location"], "https://example.com/legacy");
+
+    // Permanent redirects are only cached for a bounded time
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/moved", "redirect_type": "permanent" }),
+    )
+    .await;
+    let response = visit(&client, &base, &link).await;
+    assert_eq!(response.status(), 308);
+    assert_eq!(response.headers()["cache-control"], "private, max-age=3600");
+
+    // ...and never for expiring links
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/moved", "redirect_type": "permanent", "ttl": "10m" }),
+    )
+    .await;
+    let response = visit(&client, &base, &link).await;
+    assert_eq!(response.status(), 308);
+    assert_eq!(response.headers()["cache-control"], "no-store");
+
+    // Nor links with rules, even for visitors no rule matches
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/moved", "redirect_type": "permanent" }),
+    )
+    .await;
+    let response = client
+        .put(format!(
+            "{}/api/v1/links/{}/rules",
+            base,
+            link["id"].as_str().unwrap()
+        ))
+        .json(&json!({ "rules": [{ "platforms": ["ios"], "url": "https://apps.apple.com/app/id1" }] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let response = visit(&client, &base, &link).await;
+    assert_eq!(response.headers()["location"], "https://example.com/moved");
+    assert_eq!(response.headers()["cache-control"], "no-store");
+
+    // Click-limited links are never cached
+    let link = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/once", "redirect_type": "permanent", "max_clicks": 5 }),
+    )
+    .await;
+    let response = visit(&client, &base, &link).await;
+    assert_eq!(response.status(), 308);
+    assert_eq!(response.headers()["cache-control"], "no-store");
+
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://example.com", "redirect_type": "moved" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 422);
+}
+
+#[tokio::test]
+async fn test_server_default_redirect_type() {
+    let base = common::spawn_app(qrlink::config::Config {
+        default_redirect_type: RedirectType::Permanent,
+        ..common::test_config()
+    })
+    .await;
+    let client = common::client();
+
+    let link = create_link(&client, &base, json!({ "url": "https://example.com" })).await;
+    assert!(link.get("redirect_type").is_none());
+    assert_eq!(visit(&client, &base, &link).await.status(), 308);
+
+    // A link's own type overrides the default
+    let response = client
+        .patch(format!(
+            "{}/api/v1/links/{}",
+            base,
+            link["id"].as_str().unwrap()
+        ))
+        .json(&json!({ "redirect_type": "temporary" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(visit(&client, &base, &link).await.status(), 307);
+}
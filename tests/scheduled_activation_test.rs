@@ -0,0 +1,75 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn test_scheduled_link_shows_holding_page() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({
+            "url": "https://example.com/campaign",
+            "active_from": Utc::now() + Duration::days(2),
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["active_from"].is_string());
+
+    let response = client
+        .get(format!("{}/{}", base, body["short_code"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("location").is_none());
+    assert_eq!(response.headers()["cache-control"], "no-store");
+    assert!(response.text().await.unwrap().contains("Coming soon"));
+}
+
+#[tokio::test]
+async fn test_scheduled_link_redirects_to_prelaunch_url() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({
+            "url": "https://example.com/campaign",
+            "active_from": Utc::now() + Duration::days(2),
+            "prelaunch_url": "https://example.com/teaser",
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+
+    let response = client
+        .get(format!("{}/{}", base, body["short_code"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 307);
+    assert_eq!(response.headers()["location"], "https://example.com/teaser");
+}
+
+#[tokio::test]
+async fn test_prelaunch_url_requires_active_from() {
+    let base = common::spawn_app(common::test_config()).await;
+
+    let response = common::client()
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({
+            "url": "https://example.com/campaign",
+            "prelaunch_url": "https://example.com/teaser",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use serde_json::{Value, json};
+
+async fn create_link(client: &reqwest::Client, base: &str, body: Value) -> Value {
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_utm_parameters_added_to_target() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({
+            "url": "https://shop.example.com/?lang=en&utm_source=old",
+            "utm": { "source": "newsletter", "medium": "email", "campaign": "spring-sale" }
+        }),
+    )
+    .await;
+    assert_eq!(
+        link["target_url"],
+        "https://shop.example.com/?lang=en&utm_source=newsletter&utm_medium=email&utm_campaign=spring-sale"
+    );
+    assert_eq!(link["utm"]["campaign"], "spring-sale");
+
+    let response = client
+        .get(format!("{}/{}", base, link["short_code"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["location"],
+        link["target_url"].as_str().unwrap()
+    );
+
+    let response = client
+        .post(format!("{}/api/v1/links", base))
+        .json(&json!({ "url": "https://example.com", "utm": { "campaign": "" } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_filter_links_and_stats_by_campaign() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let tagged = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/a", "utm": { "campaign": "spring-sale" } }),
+    )
+    .await;
+    // Parameters already in the URL count too
+    let manual = create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/b?utm_campaign=spring-sale" }),
+    )
+    .await;
+    create_link(
+        &client,
+        &base,
+        json!({ "url": "https://example.com/c", "utm": { "campaign": "autumn" } }),
+    )
+    .await;
+
+    let page: Value = client
+        .get(format!("{}/api/v1/links?campaign=spring-sale", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mut ids: Vec<&str> = page["links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["id"].as_str().unwrap())
+        .collect();
+    ids.sort();
+    let mut expected = vec![
+        tagged["id"].as_str().unwrap(),
+        manual["id"].as_str().unwrap(),
+    ];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    let response = client
+        .get(format!("{}/api/v1/campaigns/spring-sale/stats", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let stats: Value = response.json().await.unwrap();
+    assert_eq!(stats["campaign"], "spring-sale");
+    assert_eq!(stats["links"], 2);
+}
+
+#[tokio::test]
+async fn test_retargeting_keeps_utm_parameters() {
+    let base = common::spawn_app(common::test_config()).await;
+    let client = common::client();
+
+    let link = create_link(
+        &client,
+        &base,
+        json!({
+            "url": "https://example.com/a",
+            "utm": { "source": "newsletter", "campaign": "spring-sale" }
+        }),
+    )
+    .await;
+    let id = link["id"].as_str().unwrap();
+
+    let response = client
+        .patch(format!("{}/api/v1/links/{}", base, id))
+        .json(&json!({ "url": "https://example.com/b?utm_source=flyer" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let updated: Value = response.json().await.unwrap();
+    assert_eq!(
+        updated["target_url"],
+        "https://example.com/b?utm_source=flyer&utm_campaign=spring-sale"
+    );
+
+    let page: Value = client
+        .get(format!("{}/api/v1/links?campaign=spring-sale", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page["links"][0]["id"], id);
+
+    let response = client
+        .patch(format!("{}/api/v1/links/{}", base, id))
+        .json(&json!({ "url": "https://example.com/c", "utm": { "campaign": "autumn" } }))
+        .send()
+        .await
+        .unwrap();
+    let updated: Value = response.json().await.unwrap();
+    assert_eq!(
+        updated["target_url"],
+        "https://example.com/c?utm_source=flyer&utm_campaign=autumn"
+    );
+}